@@ -1,22 +1,143 @@
-use quote::quote;
-use syn::Ident;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{ext::IdentExt, DeriveInput, Generics, Ident};
 
-use crate::{quote_user, user_type::UserFields};
+use crate::{
+    quote_user, typestate,
+    user_type::{ContainerAttributes, Pattern, Rule, UserFields},
+};
 
-pub(crate) fn impl_derive_builder(
-    name: &Ident,
+/// Everything a single builder is generated for: a struct, or one variant of an enum.
+pub(crate) struct Target<'a> {
+    /// The user type, which gets the entry point and is returned by `build`.
+    pub(crate) name: &'a Ident,
+    pub(crate) generics: &'a Generics,
+    /// Path the built value is constructed with, `Name` or `Name::Variant`.
+    pub(crate) constructor: TokenStream,
+    /// The entry point on the user type, `builder` or `{variant}_builder`.
+    pub(crate) entry: Ident,
+}
+
+impl Target<'_> {
+    /// What is built, `Name` or `Name::Variant`, as shown in docs and errors.
+    pub(crate) fn built(&self) -> String {
+        self.constructor.to_string().replace(' ', "")
+    }
+
+    /// The docs of the entry point.
+    pub(crate) fn entry_docs(&self) -> String {
+        format!(
+            "Creates a builder of [`{}`], with no field set.",
+            self.built()
+        )
+    }
+}
+
+pub(crate) fn impl_derive_builder(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+    let default_name = format_ident!("{}Builder", name);
+    let container = ContainerAttributes::from_attrs(ast.attrs.iter(), default_name, &ast.vis)?;
+
+    match &ast.data {
+        syn::Data::Struct(struct_data) => {
+            let target = Target {
+                name,
+                generics: &ast.generics,
+                constructor: quote!(#name),
+                entry: format_ident!("builder"),
+            };
+            impl_builder(
+                &target,
+                &container,
+                UserFields::new(&struct_data.fields, &container),
+            )
+        }
+        syn::Data::Enum(enum_data) => {
+            if container.named {
+                return Err(syn::Error::new_spanned(
+                    &container.name,
+                    "an enum gets one builder per variant, name them on the variants",
+                ));
+            }
+            if let Some(rule) = container.rules.first() {
+                return Err(syn::Error::new_spanned(
+                    rule.fields()[0],
+                    "the variants of an enum have their own fields, give rules on the variants",
+                ));
+            }
+
+            enum_data
+                .variants
+                .iter()
+                .filter(|variant| !matches!(variant.fields, syn::Fields::Unit))
+                .map(|variant| {
+                    let variant_name = &variant.ident;
+                    let variant_container = container.with_attrs(
+                        variant.attrs.iter(),
+                        format_ident!("{}{}Builder", name, variant_name),
+                    )?;
+                    if variant_container.default {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "an enum variant has no `Default` to take unset fields from",
+                        ));
+                    }
+                    let target = Target {
+                        name,
+                        generics: &ast.generics,
+                        constructor: quote!(#name::#variant_name),
+                        entry: format_ident!("{}_builder", to_snake_case(variant_name)),
+                    };
+                    impl_builder(
+                        &target,
+                        &variant_container,
+                        UserFields::new(&variant.fields, &variant_container),
+                    )
+                })
+                .collect()
+        }
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(
+            ast,
+            "Builder works only on structs and enums",
+        )),
+    }
+}
+
+fn impl_builder(
+    target: &Target,
+    container: &ContainerAttributes,
     user_fields: UserFields,
-) -> syn::Result<proc_macro2::TokenStream> {
-    let builder_struct = struct_decl(user_fields)?;
-    let builder_impl = builder_impl(name, user_fields)?;
+) -> syn::Result<TokenStream> {
+    check_names(user_fields)?;
+    if container.typestate {
+        return typestate::impl_builder(target, container, user_fields);
+    }
+
+    let Target {
+        name,
+        generics,
+        entry,
+        ..
+    } = target;
+
+    let vis = &container.vis;
+    let builder_name = &container.name;
+    let builder_struct = struct_decl(target, container, user_fields)?;
+    let builder_impl = builder_impl(target, container, user_fields)?;
+    let error = error_decl(target, container);
 
     let field_inits = user_fields.iter_attrs(quote_user::inits)?;
+    let phantom_init = phantom(generics).map(|_| quote!(__phantom: ::std::marker::PhantomData));
 
+    let entry_docs = target.entry_docs();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let user_impl = quote! {
-        impl #name {
-            pub fn builder() -> Builder {
-                Builder {
-                    #(#field_inits,)*
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #entry_docs]
+            #vis fn #entry() -> #builder_name #ty_generics {
+                #builder_name {
+                    #(#field_inits)*
+                    #phantom_init
                 }
             }
         }
@@ -25,38 +146,536 @@ pub(crate) fn impl_derive_builder(
     Ok(quote! {
         #builder_struct
         #builder_impl
+        #error
         #user_impl
     })
 }
 
-fn struct_decl(user_fields: UserFields) -> syn::Result<proc_macro2::TokenStream> {
+/// Fails on the first method of the builder whose name is already taken.
+fn check_names(user_fields: UserFields) -> syn::Result<()> {
+    let mut taken = vec![
+        ("build".to_owned(), "the method building the value"),
+        ("builder".to_owned(), "the method creating the builder"),
+    ];
+    for name in user_fields.iter_attrs(quote_user::setter_names)?.flatten() {
+        let unraw = name.unraw().to_string();
+        if let Some((_, method)) = taken.iter().find(|(taken, _)| *taken == unraw) {
+            return Err(syn::Error::new_spanned(
+                &name,
+                format!("`{}` is already the name of {}", unraw, method),
+            ));
+        }
+        taken.push((unraw, "another setter"));
+    }
+    Ok(())
+}
+
+fn struct_decl(
+    target: &Target,
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<TokenStream> {
+    let Target { generics, .. } = target;
+
+    let vis = &container.vis;
+    let builder_name = &container.name;
     let fields = user_fields.iter_attrs(quote_user::types)?;
+    let phantom = phantom(generics).map(|phantom| quote!(__phantom: #phantom));
+    let where_clause = &generics.where_clause;
+    let docs = builder_docs(target, user_fields)?;
 
     Ok(quote! {
-        struct Builder {
-            #(#fields,)*
+        #[doc = #docs]
+        #vis struct #builder_name #generics #where_clause {
+            #(#fields)*
+            #phantom
         }
     })
 }
 
-fn builder_impl(name: &Ident, user_fields: UserFields) -> syn::Result<proc_macro2::TokenStream> {
+fn builder_impl(
+    target: &Target,
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<TokenStream> {
+    let Target {
+        name,
+        generics,
+        constructor,
+        ..
+    } = target;
+
     let setters = user_fields.iter_attrs(quote_user::big_setters)?;
     let single_setters = user_fields.iter_attrs(quote_user::small_setters)?;
-    let inits = user_fields.iter_attrs(quote_user::builder)?;
+    let try_setters = user_fields.iter_attrs(quote_user::try_setters)?;
+    let checkers = user_fields.iter_attrs(quote_user::checkers)?;
+    // Bound before the fields, whose locals could shadow the functions' names.
+    let validator =
+        (container.build_fn.validate.as_ref()).map(|validate| quote!(let __validate = #validate;));
+    let validators = user_fields.iter_attrs(quote_user::validators)?;
+    let presence = presence_check(container, user_fields)?;
+    let default = default_binding(target, container, user_fields)?;
+    let bindings = user_fields.iter_attrs(quote_user::builder)?;
+    let checks = user_fields.iter_attrs(quote_user::checks)?;
+    let rules = rules_check(container, user_fields)?;
+    let built = user_fields.construct(constructor, user_fields.iter_attrs(quote_user::local)?);
+    let validation = validation(container);
+
+    let bounded = bounded_generics(generics, container);
+    let (impl_generics, ty_generics, where_clause) = bounded.split_for_impl();
 
+    let vis = &container.vis;
+    let builder_name = &container.name;
+    let error = container.build_error();
+    let receiver = match container.pattern() {
+        Pattern::Owned => quote!(self),
+        Pattern::Mutable | Pattern::Immutable => quote!(&self),
+    };
+    let build_docs = build_docs(target, container, user_fields)?;
     let build_fn = quote! {
-        fn build(&mut self) -> ::std::result::Result<#name, ::std::boxed::Box<dyn ::std::error::Error>> {
-            ::std::result::Result::Ok(#name {
-                #(#inits,)*
+        #[doc = #build_docs]
+        #vis fn build(#receiver) -> ::std::result::Result<#name #ty_generics, #error> {
+            #validator
+            #(#validators)*
+            #presence
+            #default
+            #(#bindings)*
+            #(#checks)*
+            #rules
+            let __built = #built;
+            #validation
+            ::std::result::Result::Ok(__built)
+        }
+    };
+
+    let clone_impl = match container.pattern() {
+        Pattern::Owned => None,
+        Pattern::Mutable | Pattern::Immutable => {
+            let clones = user_fields.iter_attrs(quote_user::clones)?;
+            let phantom_init =
+                phantom(generics).map(|_| quote!(__phantom: ::std::marker::PhantomData));
+            Some(quote! {
+                impl #impl_generics ::std::clone::Clone for #builder_name #ty_generics #where_clause {
+                    fn clone(&self) -> Self {
+                        #builder_name {
+                            #(#clones)*
+                            #phantom_init
+                        }
+                    }
+                }
             })
         }
     };
 
     Ok(quote! {
-        impl Builder {
+        impl #impl_generics #builder_name #ty_generics #where_clause {
             #(#setters)*
             #(#single_setters)*
+            #(#try_setters)*
+            #(#checkers)*
             #build_fn
         }
+
+        #clone_impl
     })
 }
+
+/// The docs of the builder: what it builds, and how each of its fields is set.
+pub(crate) fn builder_docs(target: &Target, user_fields: UserFields) -> syn::Result<String> {
+    let built = target.built();
+    let mut docs = format!(
+        "Builder of [`{}`], created with [`{}::{}`].\n",
+        built, target.name, target.entry
+    );
+
+    let kinds = user_fields
+        .iter_attrs(|field_ident, field_type, attrs| {
+            let field = format!("`{}`", field_ident.unraw());
+            Ok(if attrs.skip.is_some() {
+                None
+            } else if let Some(each) = &attrs.each {
+                Some((
+                    3,
+                    format!("{} (one element with `{}`)", field, each.unraw()),
+                ))
+            } else if attrs.default.is_some() || attrs.container_default() {
+                Some((2, field))
+            } else if quote_user::is_required(field_type, &attrs)? {
+                Some((0, field))
+            } else {
+                Some((1, field))
+            })
+        })?
+        .flatten()
+        .collect::<Vec<_>>();
+    let titles = [
+        "Required fields",
+        "Optional fields",
+        "Fields with a default",
+        "Repeated fields",
+    ];
+    for (kind, title) in titles.iter().enumerate() {
+        let fields = kinds
+            .iter()
+            .filter(|(field_kind, _)| *field_kind == kind)
+            .map(|(_, field)| field.as_str())
+            .collect::<Vec<_>>();
+        if !fields.is_empty() {
+            docs += &format!("\n- {}: {}", title, fields.join(", "));
+        }
+    }
+    Ok(docs)
+}
+
+/// The docs of `build`, listing the errors it can fail with.
+fn build_docs(
+    target: &Target,
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<String> {
+    let error_name = container.error_name();
+    let required = user_fields
+        .iter_attrs(quote_user::presence)?
+        .flatten()
+        .map(|presence| format!("`{}`", presence.name))
+        .collect::<Vec<_>>();
+    let checked = user_fields
+        .iter_attrs(|_, _, attrs| Ok(!attrs.checks.is_empty() && !attrs.checked_setter()))?
+        .any(|checked| checked);
+
+    let mut errors = Vec::new();
+    if !required.is_empty() {
+        errors.push(format!(
+            "`{}::UninitializedFields` if any of {} is unset.",
+            error_name,
+            required.join(", ")
+        ));
+    }
+    if checked {
+        errors.push(format!(
+            "`{}::InvalidField` if a field fails one of its checks.",
+            error_name
+        ));
+    }
+    if !container.rules.is_empty() || container.build_fn.validate.is_some() {
+        errors.push(format!(
+            "`{}::ValidationError` if the value fails validation.",
+            error_name
+        ));
+    }
+
+    let mut docs = format!("Builds [`{}`] from the fields set so far.", target.built());
+    if !errors.is_empty() {
+        docs += "\n\n# Errors\n";
+        for error in errors {
+            docs += &format!("\n- {}", error);
+        }
+        if let Some(error) = &container.build_fn.error {
+            docs += &format!(
+                "\n\nEach is converted into `{}`.",
+                error.to_token_stream().to_string().replace(' ', "")
+            );
+        }
+    }
+    Ok(docs)
+}
+
+/// Binds every required field to a local of its name, or fails with all of the
+/// missing ones, in declaration order. Nothing is cloned before all are known to be set.
+fn presence_check(
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<TokenStream> {
+    let required = user_fields
+        .iter_attrs(quote_user::presence)?
+        .flatten()
+        .collect::<Vec<_>>();
+    if required.is_empty() {
+        return Ok(quote!());
+    }
+
+    let count = required.len();
+    let error_name = container.error_name();
+    let idents = required
+        .iter()
+        .map(|presence| &presence.ident)
+        .collect::<Vec<_>>();
+    let names = required.iter().map(|presence| &presence.name);
+    let accesses = required.iter().map(|presence| &presence.access);
+    let values = required.iter().map(|presence| &presence.value);
+
+    Ok(quote! {
+        let (#(#idents,)*) = match (#(#accesses,)*) {
+            (#(::std::option::Option::Some(#idents),)*) => (#(#values,)*),
+            (#(#idents,)*) => {
+                let mut __missing = ::std::vec::Vec::with_capacity(#count);
+                #(
+                    if #idents.is_none() {
+                        __missing.push(#names);
+                    }
+                )*
+                return ::std::result::Result::Err(::std::convert::From::from(
+                    #error_name::UninitializedFields(__missing),
+                ));
+            }
+        };
+    })
+}
+
+/// Checks the rules of the container on the locals of the optional fields they name,
+/// which have to exist.
+fn rules_check(
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<TokenStream> {
+    if container.rules.is_empty() {
+        return Ok(quote!());
+    }
+
+    let fields = user_fields
+        .iter_attrs(|field_ident, field_type, _| {
+            Ok((field_ident.clone(), field_type.is_option()?))
+        })?
+        .collect::<Vec<_>>();
+    for field in container.rules.iter().flat_map(Rule::fields) {
+        match fields.iter().find(|(ident, _)| ident == field) {
+            None => {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("no field `{}` to check", field.unraw()),
+                ))
+            }
+            Some((_, false)) => {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!(
+                        "`{}` is not an `Option`, so it is always set",
+                        field.unraw()
+                    ),
+                ))
+            }
+            Some((_, true)) => {}
+        }
+    }
+
+    let listed = |fields: &[Ident]| {
+        fields
+            .iter()
+            .map(|field| format!("`{}`", field.unraw()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let error_name = container.error_name();
+    let checks = container.rules.iter().map(|rule| {
+        let (failed, message) = match rule {
+            Rule::ExactlyOneOf(fields) => (
+                quote! {
+                    [#(#fields.is_some()),*].iter().filter(|set| **set).count() != 1
+                },
+                format!("exactly one of {} must be set", listed(fields)),
+            ),
+            Rule::AtLeastOneOf(fields) => (
+                quote!(#(#fields.is_none())&&*),
+                format!("at least one of {} must be set", listed(fields)),
+            ),
+            Rule::Requires(field, required) => (
+                quote!(#field.is_some() && #required.is_none()),
+                format!(
+                    "`{}` requires `{}` to be set",
+                    field.unraw(),
+                    required.unraw()
+                ),
+            ),
+        };
+        quote! {
+            if #failed {
+                return ::std::result::Result::Err(::std::convert::From::from(
+                    #error_name::ValidationError(::std::string::String::from(#message)),
+                ));
+            }
+        }
+    });
+    Ok(quote!(#(#checks)*))
+}
+
+/// Runs `build_fn(validate = "...")`, bound as `__validate`, on the built value,
+/// returning its error as a validation error.
+fn validation(container: &ContainerAttributes) -> Option<TokenStream> {
+    container.build_fn.validate.as_ref()?;
+    let error_name = container.error_name();
+    Some(quote! {
+        if let ::std::result::Result::Err(__error) = __validate(&__built) {
+            return ::std::result::Result::Err(::std::convert::From::from(
+                #error_name::ValidationError(::std::string::ToString::to_string(&__error)),
+            ));
+        }
+    })
+}
+
+/// Binds the fields of the struct's `Default` value that are used by `build`,
+/// when asked to with a container-level `#[builder(default)]`.
+pub(crate) fn default_binding(
+    target: &Target,
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<TokenStream> {
+    if !container.default {
+        return Ok(quote!());
+    }
+
+    let Target {
+        name,
+        generics,
+        constructor,
+        ..
+    } = target;
+    let bindings = user_fields.iter_attrs(|field_ident, _, attrs| {
+        Ok(if attrs.container_default() {
+            quote_user::container_default(field_ident).into_token_stream()
+        } else {
+            quote!(_)
+        })
+    })?;
+    let pattern = user_fields.construct(constructor, bindings);
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    Ok(quote! {
+        let #pattern: #name #ty_generics = ::std::default::Default::default();
+    })
+}
+
+/// The error type returned by `build` and the setters that can fail, named after the builder.
+pub(crate) fn error_decl(target: &Target, container: &ContainerAttributes) -> TokenStream {
+    let vis = &container.vis;
+    let error_name = container.error_name();
+    let built = target.built();
+    let builder_name = &container.name;
+    let docs = format!(
+        "Error of [`{}`], returned by `build` and the setters that can fail.",
+        builder_name
+    );
+
+    quote! {
+        #[doc = #docs]
+        #[derive(::std::fmt::Debug, ::std::clone::Clone, ::std::cmp::PartialEq, ::std::cmp::Eq)]
+        #vis enum #error_name {
+            /// Required fields were never set, in declaration order.
+            UninitializedFields(::std::vec::Vec<&'static str>),
+            /// The built value did not pass validation.
+            ValidationError(::std::string::String),
+            /// A `try_` setter could not convert its argument into the field's type.
+            ConversionError {
+                /// The field, named as its setter.
+                field: &'static str,
+                /// What the conversion failed with.
+                message: ::std::string::String,
+            },
+            /// A field did not pass one of its checks, given as the `rule` it is written with.
+            InvalidField {
+                /// The field, named as its setter.
+                field: &'static str,
+                /// The check, as written in the attribute.
+                rule: &'static str,
+                /// How the value failed the check.
+                message: ::std::string::String,
+            },
+        }
+
+        impl ::std::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    Self::UninitializedFields(fields) => {
+                        ::std::write!(f, "`{}` is missing required field", #built)?;
+                        if fields.len() > 1 {
+                            f.write_str("s")?;
+                        }
+                        for (index, field) in fields.iter().enumerate() {
+                            let separator = if index == 0 { " " } else { ", " };
+                            ::std::write!(f, "{}`{}`", separator, field)?;
+                        }
+                        ::std::result::Result::Ok(())
+                    }
+                    Self::ValidationError(message) => {
+                        ::std::write!(f, "invalid `{}`: {}", #built, message)
+                    }
+                    Self::ConversionError { field, message } => {
+                        ::std::write!(f, "cannot set `{}` of `{}`: {}", field, #built, message)
+                    }
+                    Self::InvalidField {
+                        field,
+                        rule,
+                        message,
+                    } => {
+                        ::std::write!(
+                            f,
+                            "`{}` of `{}` failed `{}`: {}",
+                            field,
+                            #built,
+                            rule,
+                            message
+                        )
+                    }
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+    }
+}
+
+/// Adds the bounds needed by the builder impl: either the ones given with
+/// `#[builder(bound = "...")]`, or `Clone` on every type parameter, since `build` clones
+/// unless the builder is owned.
+pub(crate) fn bounded_generics(generics: &Generics, container: &ContainerAttributes) -> Generics {
+    let mut bounded = generics.clone();
+    let predicates = match &container.bound {
+        Some(bound) => bound.iter().cloned().collect(),
+        None if container.pattern() == Pattern::Owned => Vec::new(),
+        None => generics
+            .type_params()
+            .map(|param| {
+                let ident = &param.ident;
+                syn::parse_quote!(#ident: ::std::clone::Clone)
+            })
+            .collect::<Vec<syn::WherePredicate>>(),
+    };
+    bounded.make_where_clause().predicates.extend(predicates);
+    bounded
+}
+
+/// A marker type using every type and lifetime parameter, since the builder of an enum
+/// variant need not store all of them.
+pub(crate) fn phantom(generics: &Generics) -> Option<TokenStream> {
+    let types = generics.type_params().map(|param| &param.ident);
+    let lifetimes = generics.lifetimes().map(|param| &param.lifetime);
+    if generics.type_params().next().is_none() && generics.lifetimes().next().is_none() {
+        return None;
+    }
+    Some(quote! {
+        ::std::marker::PhantomData<fn() -> (#(#types,)* #(&#lifetimes (),)*)>
+    })
+}
+
+/// `RequestSent` becomes `request_sent`, and `HTTPRequest` becomes `http_request`.
+fn to_snake_case(ident: &Ident) -> String {
+    let chars: Vec<char> = ident.to_string().chars().collect();
+    let mut snake = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = index.checked_sub(1).map(|prev| chars[prev]);
+            let next = chars.get(index + 1);
+            let word_start = match prev {
+                None | Some('_') => false,
+                Some(prev) if prev.is_uppercase() => next.is_some_and(|next| next.is_lowercase()),
+                Some(_) => true,
+            };
+            if word_start {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
@@ -3,20 +3,16 @@ use syn::DeriveInput;
 use crate::builders::impl_derive_builder;
 
 mod builders;
+mod options;
 pub(crate) mod quote_user;
+mod typestate;
 pub(crate) mod user_type;
 
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: DeriveInput = syn::parse(input).expect("Not a derive input");
+    let ast = syn::parse_macro_input!(input as DeriveInput);
 
-    let name = &ast.ident;
-    let syn::Data::Struct(struct_data) = &ast.data else {
-        return syn::Error::new_spanned(ast, "Builder works only on types")
-            .to_compile_error()
-            .into();
-    };
-    match impl_derive_builder(name, (&struct_data.fields).into()) {
+    match impl_derive_builder(&ast) {
         Ok(token_stream) => token_stream.into(),
         Err(e) => e.to_compile_error().into(),
     }
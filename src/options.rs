@@ -0,0 +1,244 @@
+//! The syntax of `#[builder(...)]`: options separated by commas, each a flag `key`,
+//! a value `key = value` or a list `key(...)` of further options. A value is either a
+//! string literal, whose contents are parsed, or an expression for `default` and `skip`,
+//! or otherwise the tokens up to the next comma.
+
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+};
+
+/// The keys whose value is an expression, which may contain commas and `<`.
+const EXPRESSION_KEYS: [&str; 2] = ["default", "skip"];
+
+pub(crate) struct BuilderOption {
+    pub(crate) key: syn::Ident,
+    pub(crate) value: OptionValue,
+}
+
+pub(crate) enum OptionValue {
+    /// `key`
+    Flag,
+    /// `key = value`
+    Value(TokenStream),
+    /// `key(...)`, with the tokens between the parentheses.
+    List(TokenStream),
+}
+
+impl Parse for BuilderOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.call(syn::Ident::parse_any)?;
+        let value = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            OptionValue::List(content.parse()?)
+        } else if input.peek(syn::Token![=]) {
+            input.parse::<syn::Token![=]>()?;
+            if EXPRESSION_KEYS.iter().any(|expression| key == expression) {
+                OptionValue::Value(input.parse::<syn::Expr>()?.into_token_stream())
+            } else {
+                OptionValue::Value(input.call(value_tokens)?)
+            }
+        } else {
+            OptionValue::Flag
+        };
+        Ok(BuilderOption { key, value })
+    }
+}
+
+impl BuilderOption {
+    pub(crate) fn name(&self) -> String {
+        self.key.unraw().to_string()
+    }
+
+    /// Fails unless the option is a flag, `key`.
+    pub(crate) fn flag(&self) -> syn::Result<()> {
+        match self.value {
+            OptionValue::Flag => Ok(()),
+            _ => Err(self.error(format!("`{}` takes no value", self.name()))),
+        }
+    }
+
+    /// `key`, or `key = true` and `key = false`.
+    pub(crate) fn bool(&self) -> syn::Result<bool> {
+        match self.value {
+            OptionValue::Flag => Ok(true),
+            _ => Ok(self.value::<syn::LitBool>()?.value),
+        }
+    }
+
+    /// Parses the value of `key = value`.
+    pub(crate) fn value<T: Parse>(&self) -> syn::Result<T> {
+        self.value_with(T::parse)
+    }
+
+    pub(crate) fn value_with<P: Parser>(&self, parser: P) -> syn::Result<P::Output> {
+        let OptionValue::Value(tokens) = &self.value else {
+            return Err(self.error(format!("expected `{} = ...`", self.name())));
+        };
+        // An error at the end of the value would otherwise point at the derive.
+        match syn::parse2::<syn::LitStr>(tokens.clone()) {
+            Ok(lit) => lit
+                .parse_with(parser)
+                .map_err(|error| syn::Error::new(lit.span(), error)),
+            Err(_) => parser
+                .parse2(tokens.clone())
+                .map_err(|error| syn::Error::new_spanned(tokens, error)),
+        }
+    }
+
+    /// The string literal of `key = "..."` itself, rather than what it contains.
+    pub(crate) fn lit_str(&self) -> syn::Result<syn::LitStr> {
+        match &self.value {
+            OptionValue::Value(tokens) => syn::parse2(tokens.clone()),
+            _ => Err(self.error(format!("expected `{} = \"...\"`", self.name()))),
+        }
+    }
+
+    /// The options of the list `key(...)`, each key given once.
+    pub(crate) fn list(&self) -> syn::Result<Vec<BuilderOption>> {
+        let options = self.list_with(parse_options)?;
+        check_duplicates(&options, &[])?;
+        Ok(options)
+    }
+
+    pub(crate) fn list_with<P: Parser>(&self, parser: P) -> syn::Result<P::Output> {
+        let OptionValue::List(tokens) = &self.value else {
+            return Err(self.error(format!("expected `{}(...)`", self.name())));
+        };
+        // As for a value, an error at the end of the list would point at the derive.
+        parser.parse2(tokens.clone()).map_err(|error| {
+            if tokens.is_empty() {
+                self.error(error)
+            } else {
+                syn::Error::new_spanned(tokens, error)
+            }
+        })
+    }
+
+    pub(crate) fn error(&self, message: impl std::fmt::Display) -> syn::Error {
+        syn::Error::new_spanned(&self.key, message)
+    }
+
+    /// The error of an option that is none of `keys`, suggesting the closest one or,
+    /// if none is close, listing them. A key given as `setter(into)` is only suggested,
+    /// for an option that belongs in that list.
+    pub(crate) fn unknown(&self, what: &str, keys: &[&str]) -> syn::Error {
+        let name = self.name();
+        let closest = keys
+            .iter()
+            .map(|key| {
+                let compared = key
+                    .split_once('(')
+                    .map_or(*key, |(_, inner)| inner.trim_end_matches(')'));
+                (distance(&name, compared), key)
+            })
+            .filter(|(distance, _)| *distance <= (name.len() / 3).max(1))
+            .min_by_key(|(distance, _)| *distance);
+        if let Some((_, key)) = closest {
+            return self.error(format!("unknown {}, did you mean: `{}`?", what, key));
+        }
+
+        let valid = keys
+            .iter()
+            .filter(|key| !key.contains('('))
+            .map(|key| format!("`{}`", key))
+            .collect::<Vec<_>>();
+        self.error(format!(
+            "unknown {} `{}`\nnote: the valid options are {}",
+            what,
+            name,
+            valid.join(", ")
+        ))
+    }
+}
+
+/// The edit distance between `a` and `b`, counting insertions, deletions, substitutions
+/// and swaps of adjacent characters.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            table[i][j] = if i == 0 || j == 0 {
+                i + j
+            } else {
+                let substituted = table[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+                let mut distance = substituted
+                    .min(table[i - 1][j] + 1)
+                    .min(table[i][j - 1] + 1);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    distance = distance.min(table[i - 2][j - 2] + 1);
+                }
+                distance
+            };
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+/// The options of every `#[builder(...)]` among `attrs`, in order, failing on a key
+/// given twice unless it is `repeatable`.
+pub(crate) fn parse_attrs<'a>(
+    attrs: impl Iterator<Item = &'a syn::Attribute>,
+    repeatable: &[&str],
+) -> syn::Result<Vec<BuilderOption>> {
+    let mut options = Vec::new();
+    for attr in attrs.filter(|attr| attr.path.is_ident("builder")) {
+        options.extend(attr.parse_args_with(parse_options)?);
+    }
+    check_duplicates(&options, repeatable)?;
+    Ok(options)
+}
+
+fn parse_options(input: ParseStream) -> syn::Result<Vec<BuilderOption>> {
+    let options = Punctuated::<BuilderOption, syn::Token![,]>::parse_terminated(input)?;
+    Ok(options.into_iter().collect())
+}
+
+fn check_duplicates(options: &[BuilderOption], repeatable: &[&str]) -> syn::Result<()> {
+    for (index, option) in options.iter().enumerate() {
+        let name = option.name();
+        if !repeatable.contains(&name.as_str())
+            && options[..index].iter().any(|other| other.name() == name)
+        {
+            return Err(option.error(format!("`{}` is given more than once", name)));
+        }
+    }
+    Ok(())
+}
+
+/// The tokens up to the next comma that is not between angle brackets, as in
+/// `error = Error<String, u32>`. Every lone `<` opens one, so comparisons belong in
+/// expressions or string literals.
+fn value_tokens(input: ParseStream) -> syn::Result<TokenStream> {
+    input.step(|cursor| {
+        let mut rest = *cursor;
+        let mut tokens = TokenStream::new();
+        let mut depth = 0usize;
+        let mut joined = None;
+        while let Some((token, next)) = rest.token_tree() {
+            if let TokenTree::Punct(punct) = &token {
+                match punct.as_char() {
+                    ',' if depth == 0 => break,
+                    '<' if punct.spacing() == Spacing::Alone => depth += 1,
+                    // Not the end of `->` or `=>`.
+                    '>' if !matches!(joined, Some('-' | '=')) => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                joined = (punct.spacing() == Spacing::Joint).then(|| punct.as_char());
+            } else {
+                joined = None;
+            }
+            tokens.extend(std::iter::once(token));
+            rest = next;
+        }
+        if tokens.is_empty() {
+            return Err(cursor.error("expected a value"));
+        }
+        Ok((tokens, rest))
+    })
+}
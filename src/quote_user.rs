@@ -1,7 +1,8 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
 
-use crate::user_type::{Attributes, UserType};
+use crate::user_type::{Attributes, Check, Collection, FieldDefault, Pattern, TrySetter, UserType};
 
 pub(crate) fn types(
     field_ident: &syn::Ident,
@@ -9,75 +10,388 @@ pub(crate) fn types(
     attrs: Attributes,
 ) -> syn::Result<TokenStream> {
     let inner_type = field_type.expected_type()?;
-    Ok(if attrs.each.is_some() {
+    Ok(if attrs.skip.is_some() {
+        quote! {}
+    } else if is_collection(field_type, &attrs)? {
         quote! {
-            #field_ident: #inner_type
+            #field_ident: #inner_type,
         }
     } else {
         quote! {
-            #field_ident: ::std::option::Option<#inner_type>
+            #field_ident: ::std::option::Option<#inner_type>,
         }
     })
 }
 
+/// Whether the builder stores the collection of `each` itself, rather than in an `Option`
+/// as for every other field, including an optional collection.
+fn is_collection(field_type: UserType, attrs: &Attributes) -> syn::Result<bool> {
+    Ok(attrs.each.is_some() && !field_type.is_option()?)
+}
+
+/// Whether `build` can only succeed once the field is set.
+pub(crate) fn is_required(field_type: UserType, attrs: &Attributes) -> syn::Result<bool> {
+    Ok(!field_type.is_option()?
+        && attrs.skip.is_none()
+        && attrs.each.is_none()
+        && attrs.default.is_none()
+        && !attrs.container_default())
+}
+
+/// Binds the value of the field to a local of its name, in declaration order, so that
+/// defaults may refer to the fields before them. Required fields were already bound
+/// by their presence check, see `presence`.
 pub(crate) fn builder(
     field_ident: &syn::Ident,
     field_type: UserType,
     attrs: Attributes,
 ) -> syn::Result<TokenStream> {
-    let is_option = field_type.is_option()?;
-    if is_option || attrs.each.is_some() {
-        Ok(quote!(
-            #field_ident: self.#field_ident.clone()
-        ))
+    if is_required(field_type, &attrs)? {
+        return Ok(quote!());
+    }
+
+    match &attrs.skip {
+        Some(FieldDefault::Trait) => {
+            return Ok(quote!(let #field_ident = ::std::default::Default::default();));
+        }
+        Some(FieldDefault::Expr(expr)) => return Ok(quote!(let #field_ident = #expr;)),
+        None => {}
+    }
+
+    let value = take(field_ident, &attrs);
+    let default = match &attrs.default {
+        _ if attrs.container_default() => {
+            let default_ident = container_default(field_ident);
+            quote!(#default_ident)
+        }
+        // A collection is never unset, its default is where it starts, see `inits`.
+        Some(_) if is_collection(field_type, &attrs)? => {
+            return Ok(quote!(let #field_ident = #value;));
+        }
+        Some(FieldDefault::Trait) => quote!(::std::default::Default::default()),
+        Some(FieldDefault::Expr(expr)) => quote!(#expr),
+        None => return Ok(quote!(let #field_ident = #value;)),
+    };
+
+    Ok(if field_type.is_option()? {
+        quote! {
+            let #field_ident = match #value {
+                ::std::option::Option::Some(__value) => ::std::option::Option::Some(__value),
+                ::std::option::Option::None => #default,
+            };
+        }
+    } else {
+        quote! {
+            let #field_ident = match #value {
+                ::std::option::Option::Some(__value) => __value,
+                ::std::option::Option::None => #default,
+            };
+        }
+    })
+}
+
+/// Binds the function of the field's `validate`, if it has one, before `build` binds
+/// the fields, as a field of the same name would shadow it.
+pub(crate) fn validators(
+    field_ident: &syn::Ident,
+    _: UserType,
+    attrs: Attributes,
+) -> syn::Result<TokenStream> {
+    let validate = attrs.checks.iter().find_map(|check| match check {
+        Check::Validate(path) => Some(path),
+        _ => None,
+    });
+    let Some(validate) = validate else {
+        return Ok(quote!());
+    };
+    if attrs.checked_setter() {
+        return Ok(quote!());
+    }
+
+    let validator = validator(field_ident);
+    Ok(quote!(let #validator = #validate;))
+}
+
+fn validator(field_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("__validate_{}", field_ident.unraw())
+}
+
+/// Runs the checks of the field on its local, as bound by `builder`, failing `build`
+/// with the first one that does not pass. An unset optional field is not checked.
+pub(crate) fn checks(
+    field_ident: &syn::Ident,
+    field_type: UserType,
+    attrs: Attributes,
+) -> syn::Result<TokenStream> {
+    if attrs.checks.is_empty() || attrs.checked_setter() {
+        return Ok(quote!());
+    }
+
+    let validator = validator(field_ident);
+    let checks = failures(field_ident, &attrs, &quote!(#validator));
+    Ok(if field_type.is_option()? {
+        quote! {
+            if let ::std::option::Option::Some(__value) = &#field_ident {
+                #(#checks)*
+            }
+        }
     } else {
-        Ok(quote! {
-            #field_ident: self.#field_ident.clone().ok_or::<::std::string::String>
-                ("Field: ".to_owned() + stringify!(#field_ident))?
+        quote! {
+            {
+                let __value = &#field_ident;
+                #(#checks)*
+            }
+        }
+    })
+}
+
+/// The function running the checks of a field with `setter(checked)`, which its
+/// setters call. Having no parameter of the field's name, it sees its `validate`.
+pub(crate) fn checkers(
+    field_ident: &syn::Ident,
+    field_type: UserType,
+    attrs: Attributes,
+) -> syn::Result<TokenStream> {
+    if attrs.skip.is_some() || !attrs.checked_setter() {
+        return Ok(quote!());
+    }
+
+    let validate = attrs.checks.iter().find_map(|check| match check {
+        Check::Validate(path) => Some(quote!(#path)),
+        _ => None,
+    });
+    let checks = failures(field_ident, &attrs, &validate.unwrap_or_default());
+    let inner_type = field_type.expected_type()?;
+    let error = attrs.container.build_error();
+    let checker = checker(field_ident);
+    Ok(quote! {
+        fn #checker(__value: &#inner_type) -> ::std::result::Result<(), #error> {
+            #(#checks)*
+            ::std::result::Result::Ok(())
+        }
+    })
+}
+
+pub(crate) fn checker(field_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("__check_{}", field_ident.unraw())
+}
+
+/// The statements failing with the first check of the field that `__value`, a reference,
+/// does not pass, calling `validate` for its `validate`.
+fn failures(
+    field_ident: &syn::Ident,
+    attrs: &Attributes,
+    validate: &TokenStream,
+) -> Vec<TokenStream> {
+    let error_name = &attrs.container.error_name();
+    let field = &field_ident.unraw().to_string();
+    attrs
+        .checks
+        .iter()
+        .flat_map(|check| {
+            let rule = check.rule();
+            let failures = match check {
+                Check::Validate(_) => vec![(
+                    quote!(let ::std::result::Result::Err(__error) = #validate(__value)),
+                    quote!(::std::string::ToString::to_string(&__error)),
+                )],
+                Check::Range { min, max } => bounds(quote!(*__value), min, max, "must be"),
+                Check::Len { min, max } => {
+                    bounds(quote!(__value.len()), min, max, "length must be")
+                }
+                Check::NonEmpty => vec![(
+                    quote!(__value.is_empty()),
+                    quote!(::std::string::String::from("must not be empty")),
+                )],
+            };
+            failures.into_iter().map(move |(failed, message)| {
+                quote! {
+                    if #failed {
+                        return ::std::result::Result::Err(::std::convert::From::from(
+                            #error_name::InvalidField {
+                                field: #field,
+                                rule: #rule,
+                                message: #message,
+                            },
+                        ));
+                    }
+                }
+            })
         })
+        .collect()
+}
+
+/// The failures of `value` against inclusive bounds, with their messages.
+fn bounds(
+    value: TokenStream,
+    min: &Option<syn::Lit>,
+    max: &Option<syn::Lit>,
+    subject: &str,
+) -> Vec<(TokenStream, TokenStream)> {
+    let min = min.iter().map(|min| {
+        let message = format!("{} at least {}", subject, min.to_token_stream());
+        (
+            quote!(#value < #min),
+            quote!(::std::string::String::from(#message)),
+        )
+    });
+    let max = max.iter().map(|max| {
+        let message = format!("{} at most {}", subject, max.to_token_stream());
+        (
+            quote!(#value > #max),
+            quote!(::std::string::String::from(#message)),
+        )
+    });
+    min.chain(max).collect()
+}
+
+/// The field's local, as bound by `builder`.
+pub(crate) fn local(
+    field_ident: &syn::Ident,
+    _: UserType,
+    _: Attributes,
+) -> syn::Result<TokenStream> {
+    Ok(quote!(#field_ident))
+}
+
+/// The local the field of the struct's own `Default` value is bound to, if it is used.
+pub(crate) fn container_default(field_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("__default_{}", field_ident)
+}
+
+/// How `build` checks that a required field is set.
+pub(crate) struct Presence {
+    pub(crate) ident: syn::Ident,
+    /// Name the field is reported with when missing.
+    pub(crate) name: String,
+    /// The stored `Option`, borrowed unless the builder is owned.
+    pub(crate) access: TokenStream,
+    /// The value, out of the `Some` bound to `ident`.
+    pub(crate) value: TokenStream,
+}
+
+pub(crate) fn presence(
+    field_ident: &syn::Ident,
+    field_type: UserType,
+    attrs: Attributes,
+) -> syn::Result<Option<Presence>> {
+    if !is_required(field_type, &attrs)? {
+        return Ok(None);
     }
+
+    let (access, value) = match attrs.pattern() {
+        Pattern::Owned => (quote!(self.#field_ident), quote!(#field_ident)),
+        Pattern::Mutable | Pattern::Immutable => (
+            quote!(&self.#field_ident),
+            quote!(::std::clone::Clone::clone(#field_ident)),
+        ),
+    };
+    Ok(Some(Presence {
+        ident: field_ident.clone(),
+        name: field_ident.unraw().to_string(),
+        access,
+        value,
+    }))
 }
 
+/// The field in a new builder: unset, or the collection of `each`, which starts empty
+/// or as its `default = ...`.
 pub(crate) fn inits(
     field_ident: &syn::Ident,
-    _: UserType,
+    field_type: UserType,
     attrs: Attributes,
 ) -> syn::Result<TokenStream> {
-    Ok(if attrs.each.is_some() {
+    Ok(if attrs.skip.is_some() {
+        quote! {}
+    } else if is_collection(field_type, &attrs)? {
+        let start = match &attrs.default {
+            Some(FieldDefault::Expr(expr)) => quote!(#expr),
+            _ => quote!(::std::default::Default::default()),
+        };
         quote! {
-            #field_ident: ::std::vec::Vec::new()
+            #field_ident: #start,
         }
     } else {
         quote! {
-            #field_ident: ::std::option::Option::None
+            #field_ident: ::std::option::Option::None,
         }
     })
 }
 
+/// The field of the builder's `Clone` impl.
+pub(crate) fn clones(
+    field_ident: &syn::Ident,
+    _: UserType,
+    attrs: Attributes,
+) -> syn::Result<TokenStream> {
+    if attrs.skip.is_some() {
+        return Ok(quote! {});
+    }
+
+    Ok(quote! {
+        #field_ident: ::std::clone::Clone::clone(&self.#field_ident),
+    })
+}
+
+/// The setter of the whole value, unless the setter of `each` has its name.
 pub(crate) fn big_setters(
     field_ident: &syn::Ident,
     field_type: UserType,
     attrs: Attributes,
 ) -> syn::Result<TokenStream> {
+    if attrs.skip.is_some() || attrs.each.as_ref() == Some(field_ident) {
+        return Ok(quote! {});
+    }
+
     let inner_type = field_type.expected_type()?;
-    Ok(if attrs.each.is_some() {
-        quote! {
-            pub fn #field_ident(&mut self, #field_ident: #inner_type) -> &mut Self {
-                self.#field_ident = #field_ident;
-                self
+    let (params, value) = param(field_ident, inner_type, attrs.setter_into());
+    let field = field_ident.unraw();
+    let name = attrs.setter_name(field_ident);
+    Ok(if attrs.each.is_some() && attrs.each_append {
+        let append =
+            match field_type.collection(attrs.each_collection, attrs.each_element.as_ref())? {
+                Collection::String => quote!(__collection.push_str(&__value);),
+                _ => quote!(::std::iter::Extend::extend(__collection, __value);),
+            };
+        let optional = field_type.is_option()?;
+        let summary = format!("Adds every element of the given collection to `{}`.", field);
+        setter(&name, params, &attrs, &summary, None, |builder| {
+            let collection = collection(builder, field_ident, optional);
+            quote! {
+                let __value: #inner_type = #value;
+                let __collection = #collection;
+                #append
             }
-        }
+        })
+    } else if is_collection(field_type, &attrs)? {
+        let summary = format!("Replaces the elements of `{}`.", field);
+        setter(&name, params, &attrs, &summary, None, |builder| {
+            quote! {
+                #builder.#field_ident = #value;
+            }
+        })
+    } else if attrs.checked_setter() {
+        let summary = format!("Sets `{}`, or fails if it does not pass its checks.", field);
+        let error = attrs.container.build_error();
+        let checker = checker(field_ident);
+        setter(&name, params, &attrs, &summary, Some(error), |builder| {
+            quote! {
+                let __value = #value;
+                Self::#checker(&__value)?;
+                #builder.#field_ident = ::std::option::Option::Some(__value);
+            }
+        })
     } else {
-        quote! {
-            pub fn #field_ident(&mut self, #field_ident: #inner_type) -> &mut Self {
-                let _ = self.#field_ident.insert(#field_ident);
-                self
+        let summary = format!("Sets `{}`.", field);
+        setter(&name, params, &attrs, &summary, None, |builder| {
+            quote! {
+                #builder.#field_ident = ::std::option::Option::Some(#value);
             }
-
-        }
+        })
     })
 }
 
+/// The setters of `each`: one adding an element and `extend_{field}` adding many.
 pub(crate) fn small_setters(
     field_ident: &syn::Ident,
     field_type: UserType,
@@ -86,20 +400,292 @@ pub(crate) fn small_setters(
     let Some(ident) = &attrs.each else {
         return Ok(quote! {});
     };
+    if attrs.skip.is_some() {
+        return Ok(quote! {});
+    }
+
+    let collection_kind =
+        field_type.collection(attrs.each_collection, attrs.each_element.as_ref())?;
+    let optional = field_type.is_option()?;
+    let field = field_ident.unraw();
+    let extend_name = format_ident!("extend_{}", field);
+    let element = collection_kind.element();
+    let extend = setter(
+        &extend_name,
+        quote!(iter: impl ::std::iter::IntoIterator<Item = impl ::std::convert::Into<#element>>),
+        &attrs,
+        &format!("Adds every element of `iter` to `{}`.", field),
+        None,
+        |builder| {
+            let collection = collection(builder, field_ident, optional);
+            quote! {
+                let __collection = #collection;
+                ::std::iter::Extend::extend(
+                    __collection,
+                    ::std::iter::Iterator::map(
+                        ::std::iter::IntoIterator::into_iter(iter),
+                        ::std::convert::Into::<#element>::into,
+                    ),
+                );
+            }
+        },
+    );
+    let into = attrs.each_into();
+    let (params, add) = match collection_kind {
+        Collection::Method(element, method) => {
+            let (params, value) = param(ident, &element, into);
+            (params, quote!(__collection.#method(#value);))
+        }
+        Collection::Map(key_type, value_type) => {
+            let (key_param, key) = param(&format_ident!("key"), &key_type, into);
+            let (value_param, value) = param(&format_ident!("value"), &value_type, into);
+            (
+                quote!(#key_param, #value_param),
+                quote!(__collection.insert(#key, #value);),
+            )
+        }
+        Collection::String => {
+            let (params, value) = param(ident, &collection_kind.element(), into);
+            (params, quote!(__collection.push(#value);))
+        }
+        Collection::Extend(element) => {
+            let (params, value) = param(ident, &element, into);
+            (
+                params,
+                quote!(::std::iter::Extend::extend(__collection, ::std::iter::once(#value));),
+            )
+        }
+    };
+    let summary = format!("Adds an element to `{}`.", field);
+    let add = setter(ident, params, &attrs, &summary, None, |builder| {
+        let collection = collection(builder, field_ident, optional);
+        quote! {
+            let __collection = #collection;
+            #add
+        }
+    });
+    Ok(quote! {
+        #add
+        #extend
+    })
+}
+
+/// The collection of `each` in the builder, by `&mut`, created if it is an unset `Option`.
+fn collection(builder: &TokenStream, field_ident: &syn::Ident, optional: bool) -> TokenStream {
+    if optional {
+        quote!(#builder.#field_ident.get_or_insert_with(::std::default::Default::default))
+    } else {
+        quote!(&mut #builder.#field_ident)
+    }
+}
+
+/// The names of the methods generated on the builder for the field, in the order
+/// of the quoters above, spanned where they are given.
+pub(crate) fn setter_names(
+    field_ident: &syn::Ident,
+    _: UserType,
+    attrs: Attributes,
+) -> syn::Result<Vec<syn::Ident>> {
+    if attrs.skip.is_some() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    match &attrs.each {
+        Some(each) => {
+            if each != field_ident {
+                names.push(attrs.setter_name(field_ident));
+            }
+            names.push(each.clone());
+            names.push(format_ident!(
+                "extend_{}",
+                field_ident.unraw(),
+                span = field_ident.span()
+            ));
+        }
+        None => names.push(attrs.setter_name(field_ident)),
+    }
+    if attrs.try_setter().is_some() {
+        names.push(format_ident!(
+            "try_{}",
+            attrs.setter_name(field_ident).unraw(),
+            span = field_ident.span()
+        ));
+    }
+    Ok(names)
+}
 
-    if ident == field_ident {
+/// The fallible `try_` setter, converting its argument with `TryInto` or `FromStr`.
+pub(crate) fn try_setters(
+    field_ident: &syn::Ident,
+    field_type: UserType,
+    attrs: Attributes,
+) -> syn::Result<TokenStream> {
+    let Some(try_setter) = attrs.try_setter() else {
+        return Ok(quote! {});
+    };
+    if attrs.skip.is_some() {
         return Ok(quote! {});
     }
 
-    let inner_opt = field_type.unwrap_vec()?;
-    let inner_type: &syn::Type = inner_opt
-        .ok_or_else(|| syn::Error::new_spanned(<&syn::Type>::from(field_type), "Should be vec"))?
-        .into();
+    let inner_type = field_type.expected_type()?;
+    let name = format_ident!("try_{}", attrs.setter_name(field_ident).unraw());
+    let (params, converted) = try_param(field_ident, inner_type, try_setter);
+    let value = try_value(field_ident, converted, &attrs);
+    let error = attrs.container.build_error();
+    let stored = if is_collection(field_type, &attrs)? {
+        quote!(__value)
+    } else {
+        quote!(::std::option::Option::Some(__value))
+    };
+    let check = attrs.checked_setter().then(|| {
+        let checker = checker(field_ident);
+        quote!(Self::#checker(&__value)?;)
+    });
+    let summary = try_summary(field_ident, try_setter);
+    Ok(setter(
+        &name,
+        params,
+        &attrs,
+        &summary,
+        Some(error),
+        |builder| {
+            quote! {
+                let __value = #value;
+                #check
+                #builder.#field_ident = #stored;
+            }
+        },
+    ))
+}
+
+/// What a `try_` setter does, for its docs.
+pub(crate) fn try_summary(field_ident: &syn::Ident, try_setter: TrySetter) -> String {
+    let conversion = match try_setter {
+        TrySetter::TryInto => "converted with `TryInto`",
+        TrySetter::Parse => "parsed with `FromStr`",
+    };
+    format!(
+        "Sets `{}` to a value {}, or fails if it cannot be.",
+        field_ident.unraw(),
+        conversion
+    )
+}
 
-    Ok(quote! {
-        pub fn #ident (&mut self, #ident: #inner_type) -> &mut Self {
-            self.#field_ident.push(#ident);
-            self
+/// The parameter of a `try_` setter and its conversion into `ty`, which is a `Result`.
+pub(crate) fn try_param(
+    ident: &syn::Ident,
+    ty: &syn::Type,
+    try_setter: TrySetter,
+) -> (TokenStream, TokenStream) {
+    match try_setter {
+        TrySetter::TryInto => (
+            quote!(#ident: impl ::std::convert::TryInto<#ty, Error = impl ::std::fmt::Display>),
+            quote!(::std::convert::TryInto::try_into(#ident)),
+        ),
+        TrySetter::Parse => (
+            quote!(#ident: &::std::primitive::str),
+            quote!(<#ty as ::std::str::FromStr>::from_str(#ident)),
+        ),
+    }
+}
+
+/// Unwraps the `converted` value of a `try_` setter, or returns its error from the
+/// setter as the builder's error, naming the field.
+pub(crate) fn try_value(
+    field_ident: &syn::Ident,
+    converted: TokenStream,
+    attrs: &Attributes,
+) -> TokenStream {
+    let error_name = attrs.container.error_name();
+    let field = field_ident.unraw().to_string();
+    quote! {
+        match #converted {
+            ::std::result::Result::Ok(__value) => __value,
+            ::std::result::Result::Err(__error) => {
+                return ::std::result::Result::Err(::std::convert::From::from(
+                    #error_name::ConversionError {
+                        field: #field,
+                        message: ::std::string::ToString::to_string(&__error),
+                    },
+                ));
+            }
         }
-    })
+    }
+}
+
+/// The parameter of a setter taking `ty`, and the value it passes on.
+pub(crate) fn param(ident: &syn::Ident, ty: &syn::Type, into: bool) -> (TokenStream, TokenStream) {
+    if into {
+        (
+            quote!(#ident: impl ::std::convert::Into<#ty>),
+            quote!(::std::convert::Into::into(#ident)),
+        )
+    } else {
+        (quote!(#ident: #ty), quote!(#ident))
+    }
+}
+
+/// Wraps the `body` of a setter, which modifies the given builder, according to the pattern.
+/// A `fallible` setter returns the builder in a `Result` with that error. It is documented
+/// as the field, or with the `summary` if the field is not.
+fn setter(
+    name: &syn::Ident,
+    params: TokenStream,
+    attrs: &Attributes,
+    summary: &str,
+    fallible: Option<TokenStream>,
+    body: impl FnOnce(&TokenStream) -> TokenStream,
+) -> TokenStream {
+    let vis = attrs.vis();
+    let doc = docs(attrs, summary);
+    let (receiver, ret, builder, clone) = match attrs.pattern() {
+        Pattern::Owned => (quote!(mut self), quote!(Self), quote!(self), None),
+        Pattern::Mutable => (quote!(&mut self), quote!(&mut Self), quote!(self), None),
+        Pattern::Immutable => (
+            quote!(&self),
+            quote!(Self),
+            quote!(__builder),
+            Some(quote!(let mut __builder = ::std::clone::Clone::clone(self);)),
+        ),
+    };
+    let body = body(&builder);
+    let (ret, builder) = match fallible {
+        Some(error) => (
+            quote!(::std::result::Result<#ret, #error>),
+            quote!(::std::result::Result::Ok(#builder)),
+        ),
+        None => (ret, builder),
+    };
+    quote! {
+        #doc
+        #vis fn #name(#receiver, #params) -> #ret {
+            #clone
+            #body
+            #builder
+        }
+    }
+}
+
+/// The docs of a setter of the field: `#[builder(doc = "...")]`, or else the doc comments
+/// of the field, or else the `summary`.
+pub(crate) fn docs(attrs: &Attributes, summary: &str) -> TokenStream {
+    if let Some(doc) = &attrs.setter_doc {
+        quote!(#[doc = #doc])
+    } else if !attrs.doc.is_empty() {
+        let doc = &attrs.doc;
+        quote!(#(#doc)*)
+    } else {
+        quote!(#[doc = #summary])
+    }
+}
+
+/// Gets the stored field out of the builder, by move if it is owned.
+fn take(field_ident: &syn::Ident, attrs: &Attributes) -> TokenStream {
+    match attrs.pattern() {
+        Pattern::Owned => quote!(self.#field_ident),
+        Pattern::Mutable | Pattern::Immutable => {
+            quote!(::std::clone::Clone::clone(&self.#field_ident))
+        }
+    }
 }
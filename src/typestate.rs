@@ -0,0 +1,329 @@
+//! Builders that record in their type which required fields have been set,
+//! so that `build` only exists once all of them are, `#[builder(typestate)]`.
+//!
+//! Every required field gets a type parameter on the builder, which is `()`
+//! while the field is unset and the field's type once it is, and the field is
+//! stored with that type. Setting a required field moves the builder into
+//! one with a different type. Other fields are handled as in an owned builder.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, GenericParam, Ident};
+
+use crate::{
+    builders::{bounded_generics, builder_docs, default_binding, error_decl, phantom, Target},
+    quote_user,
+    user_type::{ContainerAttributes, UserFields},
+};
+
+/// A required field and the type parameter tracking whether it is set.
+struct Required<'a> {
+    ident: Ident,
+    /// Name of its setter, `ident` unless prefixed.
+    setter: Ident,
+    ty: &'a syn::Type,
+    state: Ident,
+    vis: syn::Visibility,
+    into: bool,
+    /// Parameter of the `try_` setter and its unwrapped value, if it has one.
+    try_param: Option<(TokenStream, TokenStream)>,
+    /// The function running its checks, if it has `setter(checked)`.
+    checker: Option<Ident>,
+    doc: TokenStream,
+    try_doc: TokenStream,
+}
+
+pub(crate) fn impl_builder(
+    target: &Target,
+    container: &ContainerAttributes,
+    user_fields: UserFields,
+) -> syn::Result<TokenStream> {
+    let Target {
+        name,
+        generics,
+        constructor,
+        entry,
+    } = target;
+    let builder_name = &container.name;
+
+    let checked = user_fields
+        .iter_attrs(|field_ident, _, attrs| {
+            Ok((!attrs.checks.is_empty() && !attrs.checked_setter()).then(|| field_ident.clone()))
+        })?
+        .flatten()
+        .next();
+    if let Some(field_ident) = checked {
+        return Err(syn::Error::new_spanned(
+            field_ident,
+            "the `build` of a typestate builder cannot fail, so its fields are checked by their setters, with `setter(checked)`",
+        ));
+    }
+
+    let mut count = 0usize;
+    let fields = user_fields
+        .iter_attrs(|field_ident, field_type, attrs| {
+            if !quote_user::is_required(field_type, &attrs)? {
+                return Ok(None);
+            }
+            let state = format_ident!("__S{}", count);
+            count += 1;
+            let ty = field_type.into();
+            let try_param = attrs.try_setter().map(|try_setter| {
+                let (params, converted) = quote_user::try_param(field_ident, ty, try_setter);
+                (
+                    params,
+                    quote_user::try_value(field_ident, converted, &attrs),
+                )
+            });
+            let checker = attrs
+                .checked_setter()
+                .then(|| quote_user::checker(field_ident));
+            let summary = if checker.is_some() {
+                format!(
+                    "Sets `{}`, or fails if it does not pass its checks.",
+                    field_ident.unraw()
+                )
+            } else {
+                format!("Sets `{}`.", field_ident.unraw())
+            };
+            let try_doc = attrs
+                .try_setter()
+                .map_or_else(TokenStream::new, |try_setter| {
+                    quote_user::docs(&attrs, &quote_user::try_summary(field_ident, try_setter))
+                });
+            Ok(Some(Required {
+                ident: field_ident.clone(),
+                setter: attrs.setter_name(field_ident),
+                ty,
+                state,
+                vis: attrs.vis().clone(),
+                into: attrs.setter_into(),
+                try_param,
+                checker,
+                doc: quote_user::docs(&attrs, &summary),
+                try_doc,
+            }))
+        })?
+        .collect::<Vec<_>>();
+    let required = fields.iter().flatten().collect::<Vec<_>>();
+    let idents = user_fields
+        .iter_attrs(|field_ident, _, attrs| Ok(attrs.skip.is_none().then(|| field_ident.clone())))?
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let decls = user_fields
+        .iter_attrs(quote_user::types)?
+        .zip(&fields)
+        .map(|(decl, field)| match field {
+            Some(Required { ident, state, .. }) => quote!(#ident: #state,),
+            None => decl,
+        });
+    let inits = user_fields
+        .iter_attrs(quote_user::inits)?
+        .zip(&fields)
+        .map(|(init, field)| match field {
+            Some(Required { ident, .. }) => quote!(#ident: (),),
+            None => init,
+        });
+    let bindings = user_fields
+        .iter_attrs(quote_user::builder)?
+        .zip(&fields)
+        .map(|(binding, field)| match field {
+            Some(Required { ident, .. }) => quote!(let #ident = self.#ident;),
+            None => binding,
+        });
+    let checkers = user_fields.iter_attrs(quote_user::checkers)?;
+    let optional_setters = user_fields
+        .iter_attrs(quote_user::big_setters)?
+        .zip(user_fields.iter_attrs(quote_user::small_setters)?)
+        .zip(user_fields.iter_attrs(quote_user::try_setters)?)
+        .zip(&fields)
+        .filter(|(_, field)| field.is_none())
+        .map(|(((big, small), try_setter), _)| quote!(#big #small #try_setter));
+    let error = user_fields
+        .iter_attrs(|_, _, attrs| {
+            Ok(attrs.skip.is_none() && (attrs.try_setter().is_some() || attrs.checked_setter()))
+        })?
+        .any(|fallible| fallible)
+        .then(|| error_decl(target, container));
+
+    let vis = &container.vis;
+    let phantom_decl = phantom(generics).map(|phantom| quote!(__phantom: #phantom,));
+    let phantom_init = phantom(generics).map(|_| quote!(__phantom: ::std::marker::PhantomData,));
+    let user_args = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+        })
+        .collect::<Vec<_>>();
+    let builder_type =
+        |states: Vec<TokenStream>| quote!(#builder_name<#(#user_args,)* #(#states),*>);
+
+    let mut state_generics = (*generics).clone();
+    state_generics.params.extend(
+        required
+            .iter()
+            .map(|Required { state, .. }| -> GenericParam { syn::parse_quote!(#state = ()) }),
+    );
+    let where_clause = &generics.where_clause;
+    let docs = builder_docs(target, user_fields)?;
+    let builder_struct = quote! {
+        #[doc = #docs]
+        #vis struct #builder_name #state_generics #where_clause {
+            #(#decls)*
+            #phantom_decl
+        }
+    };
+
+    let unset = builder_type(required.iter().map(|_| quote!(())).collect());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let entry_docs = target.entry_docs();
+    let user_impl = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #entry_docs]
+            #vis fn #entry() -> #unset {
+                #builder_name {
+                    #(#inits)*
+                    #phantom_init
+                }
+            }
+        }
+    };
+
+    let required_setters = required.iter().map(|field| {
+        let Required {
+            ident,
+            setter,
+            ty,
+            vis,
+            into,
+            try_param,
+            checker,
+            doc,
+            try_doc,
+            ..
+        } = field;
+        let error = container.build_error();
+        let (params, value) = quote_user::param(ident, ty, *into);
+        let set = builder_type(
+            required
+                .iter()
+                .map(|other| {
+                    if other.ident == *ident {
+                        quote!(#ty)
+                    } else {
+                        let state = &other.state;
+                        quote!(#state)
+                    }
+                })
+                .collect(),
+        );
+        let others = idents
+            .iter()
+            .filter(|other| *other != ident)
+            .map(|other| quote!(#other: self.#other));
+        let try_setter = try_param.as_ref().map(|(params, value)| {
+            let name = format_ident!("try_{}", setter.unraw());
+            let set_value = match checker {
+                Some(_) => quote!(self.#setter(#ident)),
+                None => quote!(::std::result::Result::Ok(self.#setter(#ident))),
+            };
+            quote! {
+                #try_doc
+                #vis fn #name(self, #params) -> ::std::result::Result<#set, #error> {
+                    let #ident = #value;
+                    #set_value
+                }
+            }
+        });
+        let setter_fn = match checker {
+            Some(checker) => quote! {
+                #vis fn #setter(self, #params) -> ::std::result::Result<#set, #error> {
+                    let __value = #value;
+                    Self::#checker(&__value)?;
+                    ::std::result::Result::Ok(#builder_name {
+                        #ident: __value,
+                        #(#others,)*
+                        #phantom_init
+                    })
+                }
+            },
+            None => quote! {
+                #vis fn #setter(self, #params) -> #set {
+                    #builder_name {
+                        #ident: #value,
+                        #(#others,)*
+                        #phantom_init
+                    }
+                }
+            },
+        };
+        quote! {
+            #doc
+            #setter_fn
+
+            #try_setter
+        }
+    });
+
+    let bounded = bounded_generics(generics, container);
+    let mut setter_generics = bounded.clone();
+    setter_generics.params.extend(
+        required
+            .iter()
+            .map(|Required { state, .. }| -> GenericParam { syn::parse_quote!(#state) }),
+    );
+    let (setter_impl_generics, setter_ty_generics, setter_where_clause) =
+        setter_generics.split_for_impl();
+    let setters_impl = quote! {
+        impl #setter_impl_generics #builder_name #setter_ty_generics #setter_where_clause {
+            #(#required_setters)*
+            #(#optional_setters)*
+            #(#checkers)*
+        }
+    };
+
+    let set = builder_type(
+        required
+            .iter()
+            .map(|Required { ty, .. }| quote!(#ty))
+            .collect(),
+    );
+    let default = default_binding(target, container, user_fields)?;
+    let built = user_fields.construct(constructor, user_fields.iter_attrs(quote_user::local)?);
+    let (impl_generics, ty_generics, where_clause) = bounded.split_for_impl();
+    let build_docs = format!(
+        "Builds [`{}`], which the type of the builder tells has every required field set.",
+        target.built()
+    );
+    let build_impl = quote! {
+        impl #impl_generics #set #where_clause {
+            #[doc = #build_docs]
+            #vis fn build(self) -> #name #ty_generics {
+                #default
+                #(#bindings)*
+                #built
+            }
+        }
+    };
+
+    Ok(quote! {
+        #builder_struct
+        #setters_impl
+        #build_impl
+        #error
+        #user_impl
+    })
+}
@@ -1,7 +1,8 @@
-use std::marker::PhantomData;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{ext::IdentExt, parse::ParseStream, punctuated::Punctuated};
 
-use proc_macro2::TokenStream;
-use syn::NestedMeta;
+use crate::options::{self, BuilderOption, OptionValue};
 
 /// Represents the type in a user struct. It maybe wrapped in an Option,
 /// so this is my way to guarantee that it is always unwrapped (one way or another).
@@ -55,8 +56,68 @@ impl<'a> UserType<'a> {
         self.unwrap_generic("Option")
     }
 
-    pub(crate) fn unwrap_vec(self) -> syn::Result<Option<Self>> {
-        self.unwrap_generic("Vec")
+    /// How the setter of `each` adds an element to the collection, which may be
+    /// wrapped in an `Option`. A `custom` collection is only known to be `Extend`, of
+    /// the given `element` type or else of its first type argument.
+    pub(crate) fn collection(
+        self,
+        custom: bool,
+        element: Option<&syn::Type>,
+    ) -> syn::Result<Collection> {
+        if let (true, Some(element)) = (custom, element) {
+            return Ok(Collection::Extend(element.clone()));
+        }
+
+        let collection_type = self.expected_type()?;
+        let unknown = || {
+            syn::Error::new_spanned(
+                collection_type,
+                "`each` works on `Vec`, `VecDeque`, sets, maps and `String`, \
+                 use `each(name = \"...\", collection)` for other `Extend + Default` types",
+            )
+        };
+        let syn::Type::Path(type_path) = collection_type else {
+            return Err(unknown());
+        };
+        let Some(last) = type_path.path.segments.last() else {
+            return Err(unknown());
+        };
+        let args = match &last.arguments {
+            syn::PathArguments::AngleBracketed(arguments) => arguments
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    syn::GenericArgument::Type(arg) => Some(arg.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        match (last.ident.to_string().as_str(), args.as_slice()) {
+            (_, [element, ..]) if custom => Ok(Collection::Extend(element.clone())),
+            _ if custom => Err(syn::Error::new_spanned(
+                collection_type,
+                "the elements of a `collection` are its first type argument, \
+                 give their type with `collection = \"...\"` if it has none",
+            )),
+            ("Vec", [element, ..]) => {
+                Ok(Collection::Method(element.clone(), format_ident!("push")))
+            }
+            ("VecDeque", [element, ..]) => Ok(Collection::Method(
+                element.clone(),
+                format_ident!("push_back"),
+            )),
+            ("HashSet" | "BTreeSet", [element, ..]) => {
+                Ok(Collection::Method(element.clone(), format_ident!("insert")))
+            }
+            ("HashMap" | "BTreeMap", [key, value, ..]) => Ok(Collection::Map(
+                Box::new(key.clone()),
+                Box::new(value.clone()),
+            )),
+            ("String", []) => Ok(Collection::String),
+            _ => Err(unknown()),
+        }
     }
 
     /// Accesses the type, whether it is Option wrapped or not.
@@ -71,111 +132,833 @@ impl<'a> UserType<'a> {
     pub(crate) fn is_option(self) -> syn::Result<bool> {
         Ok(self.unwrap_option()?.is_some())
     }
+
+    /// `PhantomData` holds nothing worth setting, so such fields are skipped.
+    pub(crate) fn is_phantom_data(self) -> bool {
+        let syn::Type::Path(type_path) = self.0 else {
+            return false;
+        };
+        type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData")
+    }
 }
 
 /// Ensures types of user fields are always accessed through `UserType`
 #[derive(Clone, Copy)]
-pub(crate) struct UserFields<'a>(&'a syn::Fields);
-
-impl<'a> From<&'a syn::Fields> for UserFields<'a> {
-    fn from(value: &'a syn::Fields) -> Self {
-        UserFields(value)
-    }
+pub(crate) struct UserFields<'a> {
+    fields: &'a syn::Fields,
+    container: &'a ContainerAttributes,
 }
 
 impl<'a> UserFields<'a> {
-    pub(crate) fn iter_attrs(
+    pub(crate) fn new(fields: &'a syn::Fields, container: &'a ContainerAttributes) -> Self {
+        UserFields { fields, container }
+    }
+
+    pub(crate) fn iter_attrs<T>(
         &'a self,
-        mut quoter: impl FnMut(&'a syn::Ident, UserType<'a>, Attributes<'a>) -> syn::Result<TokenStream>,
-    ) -> syn::Result<impl Iterator<Item = TokenStream>> {
-        self.0
+        mut quoter: impl FnMut(&syn::Ident, UserType<'a>, Attributes<'a>) -> syn::Result<T>,
+    ) -> syn::Result<impl Iterator<Item = T>> {
+        self.fields
             .iter()
-            .map(|field| {
-                let field_name = field.ident.as_ref().expect("No name");
+            .enumerate()
+            .map(|(index, field)| {
                 let field_type = &field.ty;
-                let attrs = Attributes::from_attrs(field.attrs.iter())?;
+                let mut attrs = Attributes::from_attrs(field.attrs.iter(), self.container)?;
+                if UserType::from(field_type).is_phantom_data() {
+                    attrs.skip.get_or_insert(FieldDefault::Trait);
+                }
+                let field_name = match (&attrs.name, &field.ident) {
+                    (Some(name), _) | (None, Some(name)) => name.clone(),
+                    (None, None) => format_ident!("_{}", index),
+                };
+                if let (None, Some(span)) = (&attrs.each, attrs.each_singular) {
+                    attrs.each = Some(singular(&field_name, span)?);
+                }
 
-                quoter(field_name, field_type.into(), attrs)
+                quoter(&field_name, field_type.into(), attrs)
             })
             .collect::<Result<Vec<_>, _>>()
             .map(Vec::into_iter)
     }
+
+    /// Builds the user type at `path` from one expression per field, in declaration order.
+    pub(crate) fn construct(
+        &self,
+        path: &impl ToTokens,
+        values: impl Iterator<Item = TokenStream>,
+    ) -> TokenStream {
+        match self.fields {
+            syn::Fields::Named(syn::FieldsNamed { named, .. }) => {
+                let members = named.iter().map(|field| &field.ident);
+                quote! { #path { #(#members: #values,)* } }
+            }
+            syn::Fields::Unnamed(_) => quote! { #path ( #(#values,)* ) },
+            syn::Fields::Unit => quote! { #path },
+        }
+    }
+}
+
+/// A collection filled one element at a time by the setter of `each`.
+pub(crate) enum Collection {
+    /// Adds an element of the type with the method: `push`, `push_back` or `insert`.
+    Method(syn::Type, syn::Ident),
+    /// `HashMap` or `BTreeMap`, adding with `insert(key, value)`.
+    Map(Box<syn::Type>, Box<syn::Type>),
+    /// A `String`, built from `char`s with `push`.
+    String,
+    /// Any other collection, `each(name = "...", collection)`, adding with `Extend`.
+    Extend(syn::Type),
 }
 
-#[derive(Debug, Default)]
+impl Collection {
+    /// The type of the elements, a tuple of key and value for maps.
+    pub(crate) fn element(&self) -> syn::Type {
+        match self {
+            Collection::Method(element, _) | Collection::Extend(element) => element.clone(),
+            Collection::Map(key, value) => syn::parse_quote!((#key, #value)),
+            Collection::String => syn::parse_quote!(::std::primitive::char),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub(crate) struct Attributes<'a> {
     pub(crate) each: Option<syn::Ident>,
-    phantom: PhantomData<&'a u32>,
+    /// Whether the element setter of `each` converts with `Into`, `each(name = "...", into)`.
+    each_into: bool,
+    /// Where `each` was given without a name, which is then the singular of the field's.
+    each_singular: Option<Span>,
+    /// Whether the field is a collection only known to be `Extend + Default`.
+    pub(crate) each_collection: bool,
+    /// The type of the elements of that collection, `each(name = "...", collection = "...")`.
+    pub(crate) each_element: Option<syn::Type>,
+    /// Whether the setter of the whole collection appends to it, `each(replace = false)`.
+    pub(crate) each_append: bool,
+    /// Name of the setter, required to get readable setters on tuple structs.
+    name: Option<syn::Ident>,
+    vis: Option<syn::Visibility>,
+    pub(crate) default: Option<FieldDefault>,
+    /// Keeps the field out of the builder, `#[builder(skip)]` or `#[builder(skip = "...")]`.
+    pub(crate) skip: Option<FieldDefault>,
+    setter: SetterAttributes,
+    /// Checks `build` runs on the value, in the order they are given.
+    pub(crate) checks: Vec<Check>,
+    /// The doc comments of the field, copied onto its setters.
+    pub(crate) doc: Vec<&'a syn::Attribute>,
+    /// Docs of the setters replacing the field's, `#[builder(doc = "...")]`.
+    pub(crate) setter_doc: Option<syn::LitStr>,
+    pub(crate) container: &'a ContainerAttributes,
+}
+
+/// A check of the value of a field, reported with the field and its `rule` when failing.
+#[derive(Clone)]
+pub(crate) enum Check {
+    /// `validate = "path"`, calling `fn(&T) -> Result<(), E>` with `E: Display`.
+    Validate(syn::Path),
+    /// `range(min = 1, max = 10)`, either bound inclusive and optional.
+    Range {
+        min: Option<syn::Lit>,
+        max: Option<syn::Lit>,
+    },
+    /// `len(min = 1, max = 10)`, of a string or a collection.
+    Len {
+        min: Option<syn::Lit>,
+        max: Option<syn::Lit>,
+    },
+    /// `non_empty`, of a string or a collection.
+    NonEmpty,
+}
+
+impl Check {
+    /// The check as written in the attribute.
+    pub(crate) fn rule(&self) -> String {
+        let bounds = |min: &Option<syn::Lit>, max: &Option<syn::Lit>| {
+            let min = min
+                .iter()
+                .map(|min| format!("min = {}", min.to_token_stream()));
+            let max = max
+                .iter()
+                .map(|max| format!("max = {}", max.to_token_stream()));
+            min.chain(max).collect::<Vec<_>>().join(", ")
+        };
+        match self {
+            Check::Validate(path) => {
+                let path = path.to_token_stream().to_string().replace(' ', "");
+                format!("validate = {}", path)
+            }
+            Check::Range { min, max } => format!("range({})", bounds(min, max)),
+            Check::Len { min, max } => format!("len({})", bounds(min, max)),
+            Check::NonEmpty => "non_empty".to_owned(),
+        }
+    }
+
+    /// Parses the `min` and `max` of `range(...)` or `len(...)`.
+    fn parse_bounds(option: &BuilderOption) -> syn::Result<(Option<syn::Lit>, Option<syn::Lit>)> {
+        let (mut min, mut max) = (None, None);
+        for bound in option.list()? {
+            match bound.name().as_str() {
+                "min" => min = Some(bound.value()?),
+                "max" => max = Some(bound.value()?),
+                _ => {
+                    let what = format!("{} attribute", option.name());
+                    return Err(bound.unknown(&what, &["min", "max"]));
+                }
+            }
+        }
+        if min.is_none() && max.is_none() {
+            return Err(option.error("expected a `min`, a `max`, or both"));
+        }
+        Ok((min, max))
+    }
+}
+
+/// Value of a field left unset, `#[builder(default)]` or `#[builder(default = "...")]`.
+/// The collection of a field with `each` starts as it instead.
+#[derive(Clone)]
+pub(crate) enum FieldDefault {
+    Trait,
+    Expr(Box<syn::Expr>),
+}
+
+impl FieldDefault {
+    /// `default`, or `default = value` with the value as an expression.
+    fn from_option(option: &BuilderOption) -> syn::Result<Self> {
+        match option.value {
+            OptionValue::Flag => Ok(FieldDefault::Trait),
+            _ => Ok(FieldDefault::Expr(Box::new(option.value()?))),
+        }
+    }
 }
 
 impl<'a> Attributes<'a> {
-    fn new_each(ident: syn::Ident) -> Self {
+    /// The options of a field, then those that go in one of its lists.
+    const KEYS: [&'static str; 19] = [
+        "private",
+        "vis",
+        "name",
+        "default",
+        "skip",
+        "each",
+        "setter",
+        "doc",
+        "validate",
+        "range",
+        "len",
+        "non_empty",
+        "setter(into)",
+        "setter(try_into)",
+        "setter(parse)",
+        "setter(prefix)",
+        "setter(checked)",
+        "each(collection)",
+        "each(replace)",
+    ];
+    const EACH_KEYS: [&'static str; 4] = ["name", "into", "collection", "replace"];
+
+    fn new(container: &'a ContainerAttributes) -> Self {
         Attributes {
-            each: Some(ident),
-            phantom: PhantomData {},
+            each: None,
+            each_into: false,
+            each_singular: None,
+            each_collection: false,
+            each_element: None,
+            each_append: false,
+            name: None,
+            vis: None,
+            default: None,
+            skip: None,
+            setter: SetterAttributes::default(),
+            checks: Vec::new(),
+            doc: Vec::new(),
+            setter_doc: None,
+            container,
         }
     }
 
-    fn parse_each(attr: &'a syn::Attribute) -> syn::Result<syn::Ident> {
-        let syn::Meta::List(syn::MetaList { path, nested, .. }) =
-            attr.parse_meta().expect("Wrong attribute")
-        else {
-            return Err(syn::Error::new_spanned(attr, "Not a meta-list"));
-        };
+    /// Visibility of the setters, the builder's own unless overridden.
+    pub(crate) fn vis(&self) -> &syn::Visibility {
+        self.vis.as_ref().unwrap_or(&self.container.vis)
+    }
+
+    pub(crate) fn pattern(&self) -> Pattern {
+        self.container.pattern()
+    }
+
+    /// Whether the setter takes anything converting `Into` the field's type.
+    pub(crate) fn setter_into(&self) -> bool {
+        self.setter.into.or(self.container.setter.into) == Some(true)
+    }
 
-        if path.get_ident().expect("hm") != "builder" {
-            return Err(syn::Error::new_spanned(path, "Not a builder attribute"));
+    /// Name of the setter of the whole field called `field_ident`, after the prefix
+    /// unless the field was given its own name.
+    pub(crate) fn setter_name(&self, field_ident: &syn::Ident) -> syn::Ident {
+        let prefix = self
+            .setter
+            .prefix
+            .as_ref()
+            .or(self.container.setter.prefix.as_ref());
+        match prefix {
+            Some(prefix) if self.name.is_none() => format_ident!(
+                "{}_{}",
+                prefix.unraw(),
+                field_ident.unraw(),
+                span = field_ident.span()
+            ),
+            _ => field_ident.clone(),
         }
+    }
 
-        let Some(nested_meta) = nested.first() else {
-            return Err(syn::Error::new_spanned(nested, "No name"));
-        };
+    /// Whether the setters run the field's checks, which `build` then does not.
+    /// A field with `each` is always checked by `build`.
+    pub(crate) fn checked_setter(&self) -> bool {
+        !self.checks.is_empty()
+            && self.each.is_none()
+            && self.setter.checked.or(self.container.setter.checked) == Some(true)
+    }
+
+    /// The conversion of the fallible `try_` setter, if the field has one.
+    pub(crate) fn try_setter(&self) -> Option<TrySetter> {
+        let inherited = self.container.setter.try_setter();
+        match self.setter.try_setter {
+            Some((try_setter, true)) => Some(try_setter),
+            Some((try_setter, false)) => inherited.filter(|inherited| *inherited != try_setter),
+            None => inherited,
+        }
+    }
+
+    /// Whether the element setter of `each` takes anything converting `Into` the element,
+    /// also when the container's setters do and the field does not opt out.
+    pub(crate) fn each_into(&self) -> bool {
+        self.each_into
+            || self.setter.into != Some(false) && self.container.setter.into == Some(true)
+    }
+
+    /// Whether an unset field is taken from the struct's `Default` impl.
+    pub(crate) fn container_default(&self) -> bool {
+        self.container.default
+            && self.skip.is_none()
+            && self.each.is_none()
+            && self.default.is_none()
+    }
+
+    fn parse_option(&mut self, option: &BuilderOption) -> syn::Result<()> {
+        match option.name().as_str() {
+            "private" => {
+                option.flag()?;
+                self.vis = Some(syn::Visibility::Inherited);
+            }
+            "vis" => self.vis = Some(option.value()?),
+            "name" => self.name = Some(parse_method_name(option)?),
+            "default" => self.default = Some(FieldDefault::from_option(option)?),
+            "skip" => self.skip = Some(FieldDefault::from_option(option)?),
+            "each" => match option.value {
+                OptionValue::Flag => self.each_singular = Some(option.key.span()),
+                OptionValue::Value(_) => self.each = Some(parse_method_name(option)?),
+                OptionValue::List(_) => {
+                    self.each_singular = Some(option.key.span());
+                    for each in option.list()? {
+                        self.parse_each_option(&each)?;
+                    }
+                }
+            },
+            "setter" => {
+                for setter in option.list()? {
+                    match setter.name().as_str() {
+                        "name" => self.name = Some(parse_method_name(&setter)?),
+                        key if SetterAttributes::KEYS.contains(&key) => {
+                            self.setter.parse_option(&setter)?
+                        }
+                        _ => {
+                            let keys = [&SetterAttributes::KEYS[..], &["name"]].concat();
+                            return Err(setter.unknown("setter attribute", &keys));
+                        }
+                    }
+                }
+            }
+            "doc" => self.setter_doc = Some(option.lit_str()?),
+            "validate" => self.checks.push(Check::Validate(option.value()?)),
+            "range" => {
+                let (min, max) = Check::parse_bounds(option)?;
+                self.checks.push(Check::Range { min, max });
+            }
+            "len" => {
+                let (min, max) = Check::parse_bounds(option)?;
+                self.checks.push(Check::Len { min, max });
+            }
+            "non_empty" => {
+                option.flag()?;
+                self.checks.push(Check::NonEmpty);
+            }
+            _ => return Err(option.unknown("attribute", &Self::KEYS)),
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Attributes<'a> {
+    /// Options of the list form, `each(name = "arg", into, collection, replace = false)`.
+    /// The elements of a `collection` may be given, as in `collection = "(K, V)"`.
+    fn parse_each_option(&mut self, option: &BuilderOption) -> syn::Result<()> {
+        match option.name().as_str() {
+            "into" => {
+                option.flag()?;
+                self.each_into = true;
+            }
+            "collection" => {
+                self.each_element = match option.value {
+                    OptionValue::Flag => None,
+                    _ => Some(option.value()?),
+                };
+                self.each_collection = true;
+            }
+            "name" => self.each = Some(parse_method_name(option)?),
+            "replace" => self.each_append = !option.bool()?,
+            _ => return Err(option.unknown("each attribute", &Self::EACH_KEYS)),
+        }
+        Ok(())
+    }
+
+    /// Interprets the `builder` attributes of a field, and keeps its `doc` ones, ignoring
+    /// any other. Its `cfg` and `cfg_attr` are already resolved when the derive runs.
+    pub(crate) fn from_attrs(
+        attrs: impl Iterator<Item = &'a syn::Attribute> + Clone,
+        container: &'a ContainerAttributes,
+    ) -> syn::Result<Self> {
+        let mut attributes = Attributes::new(container);
+        attributes.doc = attrs
+            .clone()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .collect();
+        for option in options::parse_attrs(attrs, &[])? {
+            attributes.parse_option(&option)?;
+        }
+
+        let each = attributes.each.is_some() || attributes.each_singular.is_some();
+        if let (true, Some(span)) = (each, attributes.setter.checked_span) {
+            return Err(syn::Error::new(
+                span,
+                "a field with `each` is set one element at a time, so its checks run in `build`",
+            ));
+        }
+        Ok(attributes)
+    }
+}
+
+/// Attributes put on the user struct itself, configuring the builder as a whole.
+/// On enums, a variant may override them for its own builder.
+#[derive(Clone)]
+pub(crate) struct ContainerAttributes {
+    /// Name of the builder, `{Struct}Builder` unless overridden.
+    pub(crate) name: syn::Ident,
+    /// Whether the name was given by `#[builder(name = "...")]`.
+    pub(crate) named: bool,
+    /// Visibility of the builder, `builder()` and `build()`, by default the struct's.
+    pub(crate) vis: syn::Visibility,
+    /// Replaces the bounds the builder impl would otherwise infer.
+    pub(crate) bound: Option<Punctuated<syn::WherePredicate, syn::Token![,]>>,
+    pattern: Option<Pattern>,
+    /// Tracks the required fields in the builder's type, `#[builder(typestate)]`.
+    pub(crate) typestate: bool,
+    /// Defaults for the setters of all fields, `#[builder(setter(...))]`.
+    pub(crate) setter: SetterAttributes,
+    /// Takes unset fields from the struct's `Default` impl, `#[builder(default)]`.
+    pub(crate) default: bool,
+    pub(crate) build_fn: BuildFnAttributes,
+    /// Relationships between optional fields, checked by `build`.
+    pub(crate) rules: Vec<Rule>,
+}
+
+/// A relationship between optional fields, named as their setters are.
+#[derive(Clone)]
+pub(crate) enum Rule {
+    /// `exactly_one_of(a, b)`
+    ExactlyOneOf(Vec<syn::Ident>),
+    /// `at_least_one_of(a, b)`
+    AtLeastOneOf(Vec<syn::Ident>),
+    /// `requires(a => b)`, `b` has to be set when `a` is.
+    Requires(syn::Ident, syn::Ident),
+}
+
+impl Rule {
+    const NAMES: [&'static str; 3] = ["exactly_one_of", "at_least_one_of", "requires"];
+
+    /// The fields named by the rule.
+    pub(crate) fn fields(&self) -> Vec<&syn::Ident> {
+        match self {
+            Rule::ExactlyOneOf(fields) | Rule::AtLeastOneOf(fields) => fields.iter().collect(),
+            Rule::Requires(field, required) => vec![field, required],
+        }
+    }
+}
+
+impl Rule {
+    /// Parses `exactly_one_of(a, b)`, `at_least_one_of(a, b)` or `requires(a => b)`.
+    fn from_option(option: &BuilderOption) -> syn::Result<Self> {
+        if option.name() == "requires" {
+            return option.list_with(|input: ParseStream| {
+                let field = input.parse()?;
+                input.parse::<syn::Token![=>]>()?;
+                let required = input.parse()?;
+                Ok(Rule::Requires(field, required))
+            });
+        }
+
+        let fields =
+            option.list_with(Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated)?;
+        if fields.len() < 2 {
+            return Err(option.error("expected at least two fields"));
+        }
+        let fields = fields.into_iter().collect();
+        if option.name() == "exactly_one_of" {
+            Ok(Rule::ExactlyOneOf(fields))
+        } else {
+            Ok(Rule::AtLeastOneOf(fields))
+        }
+    }
+}
+
+/// Options of the `build` method, `#[builder(build_fn(...))]`.
+#[derive(Clone, Default)]
+pub(crate) struct BuildFnAttributes {
+    /// Error returned instead of the generated one, which has to convert into it.
+    pub(crate) error: Option<syn::Path>,
+    /// Function checking the built value, `fn(&T) -> Result<(), E>` with `E: Display`.
+    pub(crate) validate: Option<syn::Path>,
+}
 
-        let NestedMeta::Meta(parsed) = nested_meta else {
-            return Err(syn::Error::new_spanned(nested_meta, "Unexpected literal"));
+impl ContainerAttributes {
+    /// The options of the container besides rules, then those that go in one of its lists.
+    const KEYS: [&'static str; 15] = [
+        "typestate",
+        "default",
+        "build_fn",
+        "setter",
+        "name",
+        "vis",
+        "bound",
+        "pattern",
+        "setter(into)",
+        "setter(try_into)",
+        "setter(parse)",
+        "setter(prefix)",
+        "setter(checked)",
+        "build_fn(error)",
+        "build_fn(validate)",
+    ];
+
+    pub(crate) fn from_attrs<'a>(
+        attrs: impl Iterator<Item = &'a syn::Attribute>,
+        name: syn::Ident,
+        vis: &syn::Visibility,
+    ) -> syn::Result<Self> {
+        let container = ContainerAttributes {
+            name: name.clone(),
+            named: false,
+            vis: vis.clone(),
+            bound: None,
+            pattern: None,
+            typestate: false,
+            setter: SetterAttributes::default(),
+            default: false,
+            build_fn: BuildFnAttributes::default(),
+            rules: Vec::new(),
         };
+        container.with_attrs(attrs, name)
+    }
 
-        let syn::Meta::NameValue(syn::MetaNameValue { path, lit, .. }) = parsed else {
-            return Err(syn::Error::new_spanned(parsed, "Expected name-value pair"));
+    /// Applies further attributes on top of these, as those of an enum variant,
+    /// whose builder is called `name` unless they say otherwise.
+    pub(crate) fn with_attrs<'a>(
+        &self,
+        attrs: impl Iterator<Item = &'a syn::Attribute>,
+        name: syn::Ident,
+    ) -> syn::Result<Self> {
+        let mut container = ContainerAttributes {
+            name,
+            named: false,
+            ..self.clone()
         };
+        let options = options::parse_attrs(attrs, &Rule::NAMES)?;
+        for option in &options {
+            container.parse_option(option)?;
+        }
 
-        if path.get_ident().expect("Not ident") != "each" {
-            return Err(syn::Error::new_spanned(
-                path,
-                "unknown attribute, did you mean: `each`?",
+        // Spanned on `typestate`, or on what conflicts with an inherited one.
+        let Some(option) = options
+            .iter()
+            .find(|option| option.name() == "typestate")
+            .or(options.first())
+        else {
+            return Ok(container);
+        };
+        if container.typestate && container.pattern() != Pattern::Owned {
+            return Err(option.error("a typestate builder always uses the owned pattern"));
+        }
+        if container.typestate && container.build_fn.validate.is_some() {
+            return Err(option
+                .error("the `build` of a typestate builder cannot fail, so it cannot validate"));
+        }
+        if container.typestate && !container.rules.is_empty() {
+            return Err(option.error(
+                "the `build` of a typestate builder cannot fail, so it cannot check rules",
             ));
         }
+        Ok(container)
+    }
 
-        let syn::Lit::Str(lit_str) = lit else {
-            return Err(syn::Error::new_spanned(lit, "Expected string literal"));
-        };
+    /// Name of the error type generated for the builder.
+    pub(crate) fn error_name(&self) -> syn::Ident {
+        format_ident!("{}Error", self.name)
+    }
 
-        let token_stream = lit_str
-            .value()
-            .parse::<proc_macro2::TokenStream>()
-            .map_err(|e| syn::Error::new_spanned(lit, e))?;
+    /// The error `build` returns, either the generated or the user's one.
+    pub(crate) fn build_error(&self) -> TokenStream {
+        match &self.build_fn.error {
+            Some(error) => quote!(#error),
+            None => self.error_name().into_token_stream(),
+        }
+    }
 
-        syn::parse2(token_stream)
+    pub(crate) fn pattern(&self) -> Pattern {
+        match self.pattern {
+            Some(pattern) => pattern,
+            None if self.typestate => Pattern::Owned,
+            None => Pattern::default(),
+        }
+    }
+
+    fn parse_option(&mut self, option: &BuilderOption) -> syn::Result<()> {
+        match option.name().as_str() {
+            "typestate" => {
+                option.flag()?;
+                self.typestate = true;
+            }
+            "default" => {
+                option.flag()?;
+                self.default = true;
+            }
+            "build_fn" => {
+                for build_fn in option.list()? {
+                    self.build_fn.parse_option(&build_fn)?;
+                }
+            }
+            "setter" => {
+                for setter in option.list()? {
+                    self.setter.parse_option(&setter)?;
+                }
+            }
+            "name" => {
+                self.name = option.value()?;
+                self.named = true;
+            }
+            "vis" => self.vis = option.value()?,
+            "bound" => self.bound = Some(option.value_with(Punctuated::parse_terminated)?),
+            "pattern" => self.pattern = Some(Pattern::from_option(option)?),
+            name if Rule::NAMES.contains(&name) => self.rules.push(Rule::from_option(option)?),
+            _ => {
+                let keys = [&Self::KEYS[..], &Rule::NAMES].concat();
+                return Err(option.unknown("builder attribute", &keys));
+            }
+        }
+        Ok(())
     }
 }
 
-impl<'a> Attributes<'a> {
-    pub(crate) fn from_attrs(attrs: impl Iterator<Item = &'a syn::Attribute>) -> syn::Result<Self> {
-        let mut each_results = attrs.map(|attr| (Self::parse_each(attr), attr));
+/// Options of the generated setters, `#[builder(setter(...))]`.
+#[derive(Clone, Default)]
+pub(crate) struct SetterAttributes {
+    /// Setters take `impl Into<T>` rather than `T`, `into` or `into = false`.
+    into: Option<bool>,
+    /// The `try_` setter and whether it is generated, `try_into = false` turning off
+    /// one given to every field.
+    try_setter: Option<(TrySetter, bool)>,
+    /// Put before the names of setters, `prefix = "with"` names them `with_{field}`.
+    prefix: Option<syn::Ident>,
+    /// Setters run the checks of the field and fail, rather than `build`, `checked`.
+    checked: Option<bool>,
+    /// Where `checked` was turned on.
+    checked_span: Option<Span>,
+}
+
+/// How a `try_` setter converts its argument, `setter(try_into)` or `setter(parse)`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrySetter {
+    /// Takes `impl TryInto<T>`.
+    TryInto,
+    /// Takes a `&str` and parses it with `FromStr`.
+    Parse,
+}
 
-        let first_each = match each_results.next() {
-            None => return Ok(Attributes::default()),
-            Some((Err(e), _)) => return Err(e),
-            Some((Ok(ident), _)) => ident,
+impl SetterAttributes {
+    const KEYS: [&'static str; 5] = ["into", "try_into", "parse", "prefix", "checked"];
+
+    fn parse_option(&mut self, option: &BuilderOption) -> syn::Result<()> {
+        let try_setter = match option.name().as_str() {
+            "into" => {
+                self.into = Some(option.bool()?);
+                return Ok(());
+            }
+            "prefix" => {
+                self.prefix = Some(option.value_with(syn::Ident::parse_any)?);
+                return Ok(());
+            }
+            "checked" => {
+                let checked = option.bool()?;
+                self.checked = Some(checked);
+                self.checked_span = checked.then(|| option.key.span());
+                return Ok(());
+            }
+            "try_into" => TrySetter::TryInto,
+            "parse" => TrySetter::Parse,
+            _ => return Err(option.unknown("setter attribute", &Self::KEYS)),
         };
+        let enabled = option.bool()?;
+        if enabled
+            && self
+                .try_setter
+                .is_some_and(|(other, _)| other != try_setter)
+        {
+            return Err(
+                option.error("there is a single `try_` setter, use either `try_into` or `parse`")
+            );
+        }
+        if enabled || self.try_setter.is_none() {
+            self.try_setter = Some((try_setter, enabled));
+        }
+        Ok(())
+    }
 
-        if let Some((_, attr)) = each_results.next() {
-            return Err(syn::Error::new_spanned(
-                attr,
-                "unexpected second 'each' attribute",
-            ));
+    /// The `try_` setter given by these attributes alone.
+    fn try_setter(&self) -> Option<TrySetter> {
+        self.try_setter
+            .and_then(|(try_setter, enabled)| enabled.then_some(try_setter))
+    }
+}
+
+impl BuildFnAttributes {
+    fn parse_option(&mut self, option: &BuilderOption) -> syn::Result<()> {
+        match option.name().as_str() {
+            "error" => self.error = Some(option.value()?),
+            "validate" => self.validate = Some(option.value()?),
+            _ => return Err(option.unknown("build_fn attribute", &["error", "validate"])),
         }
+        Ok(())
+    }
+}
+
+/// How setters and `build` treat the builder, `#[builder(pattern = "...")]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Pattern {
+    /// Setters take and return `self`, `build` moves the fields out.
+    Owned,
+    /// Setters take and return `&mut self`, `build` clones.
+    #[default]
+    Mutable,
+    /// Setters take `&self` and return a modified clone, `build` clones.
+    Immutable,
+}
+
+impl Pattern {
+    /// Parses `pattern = owned` or `pattern = "owned"`.
+    fn from_option(option: &BuilderOption) -> syn::Result<Self> {
+        let pattern: syn::Ident = option.value()?;
+        match pattern.to_string().as_str() {
+            "owned" => Ok(Pattern::Owned),
+            "mutable" => Ok(Pattern::Mutable),
+            "immutable" => Ok(Pattern::Immutable),
+            _ => Err(syn::Error::new_spanned(
+                pattern,
+                "expected one of: `owned`, `mutable`, `immutable`",
+            )),
+        }
+    }
+}
+
+/// Names the element setter of a bare `each` after the field, in the singular:
+/// `args` becomes `arg`, `entries` becomes `entry`, `http_headers` becomes `http_header`.
+/// A plural in `-es` that could drop either `s` or `es`, as `caches`, `statuses` or
+/// `sizes`, is not guessed.
+fn singular(field_name: &syn::Ident, span: Span) -> syn::Result<syn::Ident> {
+    const IRREGULAR: [(&str, &str); 10] = [
+        ("children", "child"),
+        ("people", "person"),
+        ("men", "man"),
+        ("women", "woman"),
+        ("mice", "mouse"),
+        ("feet", "foot"),
+        ("teeth", "tooth"),
+        ("indices", "index"),
+        ("movies", "movie"),
+        ("cookies", "cookie"),
+    ];
+    const UNCHANGED: [&str; 2] = ["series", "species"];
+    let is_consonant = |c: char| c.is_ascii_alphabetic() && !"aeiou".contains(c);
 
-        Ok(Attributes::new_each(first_each))
+    let name = field_name.unraw().to_string();
+    let (prefix, word) = match name.rfind('_') {
+        Some(index) => name.split_at(index + 1),
+        None => ("", name.as_str()),
+    };
+    let singular = if let Some((_, singular)) = IRREGULAR.iter().find(|(plural, _)| *plural == word)
+    {
+        Some(singular.to_string())
+    } else if UNCHANGED.contains(&word) {
+        None
+    } else if let Some(stem) = word
+        .strip_suffix("ies")
+        .filter(|stem| stem.len() > 1 && stem.ends_with(is_consonant))
+    {
+        Some(format!("{}y", stem))
+    } else if ["sses", "shes", "xes"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        Some(word[..word.len() - 2].to_owned())
+    } else if ["ses", "zes", "ches", "ss", "us", "is"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        None
+    } else {
+        word.strip_suffix('s')
+            .filter(|stem| !stem.is_empty())
+            .map(str::to_owned)
+    };
+
+    match singular {
+        Some(singular) if singular != word => {
+            method_ident(&format!("{}{}", prefix, singular), field_name.span())
+        }
+        _ => Err(syn::Error::new(
+            span,
+            format!(
+                "cannot name the setter of one element of `{}`, give it as `each = \"...\"`",
+                name
+            ),
+        )),
+    }
+}
+
+/// Parses the name of a method, as in `#[builder(each = arg)]` or `#[builder(each = "arg")]`.
+fn parse_method_name(option: &BuilderOption) -> syn::Result<syn::Ident> {
+    let ident = option.value_with(syn::Ident::parse_any)?;
+    method_ident(&ident.unraw().to_string(), ident.span())
+}
+
+/// The identifier of a method named `name`, raw if it is a keyword such as `type`.
+fn method_ident(name: &str, span: Span) -> syn::Result<syn::Ident> {
+    if syn::parse_str::<syn::Ident>(name).is_ok() {
+        return Ok(syn::Ident::new(name, span));
+    }
+    match name {
+        "self" | "Self" | "super" | "crate" | "_" => Err(syn::Error::new(
+            span,
+            format!("`{}` cannot be the name of a method", name),
+        )),
+        _ => Ok(syn::Ident::new_raw(name, span)),
     }
 }
@@ -0,0 +1,37 @@
+// The builder type is named after the struct, so several derives can share a
+// module, and a user type that happens to be called `Builder` is left alone.
+// The name can be overridden with a container attribute.
+//
+//     #[derive(Builder)]
+//     #[builder(name = "JobConfigurator")]
+//     pub struct Job { ... }
+
+use derive_builder::Builder;
+
+pub struct Builder;
+
+#[derive(Builder, Clone)]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+#[builder(name = "JobConfigurator")]
+pub struct Job {
+    command: Command,
+    retries: Option<u32>,
+}
+
+fn main() {
+    let command: CommandBuilder = Command::builder();
+    let mut command = command;
+    let command = command.executable("cargo".to_owned()).build().unwrap();
+
+    let mut job: JobConfigurator = Job::builder();
+    let job = job.command(command).build().unwrap();
+
+    assert_eq!(job.command.executable, "cargo");
+    assert!(job.retries.is_none());
+
+    let _ = Builder;
+}
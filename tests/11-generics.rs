@@ -0,0 +1,59 @@
+// The builder carries every type, lifetime and const parameter of the struct,
+// together with its where-clause. Since `build` clones the stored values, the
+// builder impl requires `Clone` of every type parameter, unless the bounds are
+// given explicitly with `#[builder(bound = "...")]`.
+
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use derive_builder::Builder;
+
+pub trait Codec {
+    fn encode(&self, input: &str) -> String;
+}
+
+#[derive(Clone, Debug)]
+pub struct Identity;
+
+impl Codec for Identity {
+    fn encode(&self, input: &str) -> String {
+        input.to_owned()
+    }
+}
+
+#[derive(Builder)]
+pub struct Config<'a, T: Codec, const N: usize>
+where
+    T: Debug,
+{
+    name: &'a str,
+    codec: T,
+    buffer: [u8; N],
+    fallback: Option<T>,
+}
+
+// `Rc<T>` is `Clone` for any `T`, so the inferred `T: Clone` is too strict.
+pub struct Handle;
+
+#[derive(Builder)]
+#[builder(bound = "")]
+pub struct Shared<T> {
+    value: Rc<T>,
+}
+
+fn main() {
+    let name = String::from("transport");
+    let config = Config::<_, 4>::builder()
+        .name(&name)
+        .codec(Identity)
+        .buffer([0; 4])
+        .build()
+        .unwrap();
+
+    assert_eq!(config.codec.encode(config.name), "transport");
+    assert_eq!(config.buffer.len(), 4);
+    assert!(config.fallback.is_none());
+
+    let shared = Shared::builder().value(Rc::new(Handle)).build().unwrap();
+    let _: Rc<Handle> = shared.value;
+}
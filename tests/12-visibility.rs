@@ -0,0 +1,52 @@
+// The builder type, `builder()` and `build()` take the visibility of the
+// struct, so a public struct in a submodule can be built from outside of it.
+// Container-level `#[builder(vis = "...")]` overrides that visibility, and the
+// setters of single fields can be restricted with `#[builder(private)]` or
+// `#[builder(vis = "...")]`.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Server {
+        pub host: String,
+        #[builder(vis = "pub(crate)")]
+        pub port: u16,
+        #[builder(private)]
+        secret: Option<String>,
+    }
+
+    impl Server {
+        pub fn has_secret(&self) -> bool {
+            self.secret.is_some()
+        }
+    }
+
+    impl ServerBuilder {
+        pub fn password(&mut self, password: &str) -> &mut Self {
+            self.secret(password.to_owned())
+        }
+    }
+
+    #[derive(Builder)]
+    #[builder(vis = "pub(crate)")]
+    pub struct Session {
+        pub id: u32,
+    }
+}
+
+fn main() {
+    let server = config::Server::builder()
+        .host("localhost".to_owned())
+        .port(8080)
+        .password("hunter2")
+        .build()
+        .unwrap();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert!(server.has_secret());
+
+    let mut session: config::SessionBuilder = config::Session::builder();
+    assert_eq!(session.id(7).build().unwrap().id, 7);
+}
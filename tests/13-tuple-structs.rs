@@ -0,0 +1,31 @@
+// Tuple structs get positional setters `_0`, `_1`, ... unless a field is named
+// with `#[builder(name = "...")]`, and are built with tuple syntax. Unit
+// structs get a builder with nothing to set.
+
+use derive_builder::Builder;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unit {
+    Metric,
+    Imperial,
+}
+
+#[derive(Builder)]
+pub struct Meters(f64, #[builder(name = "unit")] Unit, Option<String>);
+
+#[derive(Builder)]
+pub struct Marker;
+
+fn main() {
+    let meters = Meters::builder()
+        ._0(1.5)
+        .unit(Unit::Metric)
+        .build()
+        .unwrap();
+
+    assert_eq!(meters.0, 1.5);
+    assert_eq!(meters.1, Unit::Metric);
+    assert!(meters.2.is_none());
+
+    let Marker = Marker::builder().build().unwrap();
+}
@@ -0,0 +1,42 @@
+// Enums get one builder per struct-like or tuple variant, with an entry point
+// named after the variant. The fields of a variant are handled just like the
+// fields of a struct. A variant's builder can be renamed on the variant.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Message<T> {
+    Request {
+        id: u64,
+        #[builder(each = "header")]
+        headers: Vec<String>,
+        body: Option<T>,
+    },
+    #[builder(name = "ReplyBuilder")]
+    Reply(u64, T),
+    Ping,
+}
+
+fn main() {
+    let request: Message<String> = Message::request_builder()
+        .id(1)
+        .header("Accept: */*".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        request,
+        Message::Request {
+            id: 1,
+            headers: vec!["Accept: */*".to_owned()],
+            body: None,
+        }
+    );
+
+    let mut reply: ReplyBuilder<&str> = Message::reply_builder();
+    let reply = reply._0(1)._1("pong").build().unwrap();
+    assert_eq!(reply, Message::Reply(1, "pong"));
+
+    assert!(Message::<()>::request_builder().build().is_err());
+    let _ = Message::<()>::Ping;
+}
@@ -0,0 +1,61 @@
+// `#[builder(pattern = "...")]` selects how setters treat the builder.
+//
+//   - "mutable" (the default): setters take `&mut self` and `build` clones.
+//   - "owned": setters take and return `self`, `build(self)` moves the fields
+//     out, so neither the fields nor the builder need to be `Clone`.
+//   - "immutable": setters take `&self` and return a modified clone.
+
+use derive_builder::Builder;
+
+pub struct Connection {
+    pub peer: String,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Service {
+    connection: Connection,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+}
+
+pub struct Registry {
+    pending: ServiceBuilder,
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(pattern = "immutable")]
+pub struct Request {
+    method: String,
+    path: String,
+}
+
+fn main() {
+    let builder = Service::builder().connection(Connection {
+        peer: "10.0.0.1".to_owned(),
+    });
+    let registry = Registry {
+        pending: builder.tag("primary".to_owned()),
+    };
+    let service = registry.pending.build().unwrap();
+    assert_eq!(service.connection.peer, "10.0.0.1");
+    assert_eq!(service.tags, vec!["primary"]);
+
+    let get = Request::builder().method("GET".to_owned());
+    let index = get.path("/".to_owned()).build().unwrap();
+    let about = get.path("/about".to_owned()).build().unwrap();
+    assert_eq!(index.path, "/");
+    assert_eq!(about.path, "/about");
+    assert!(get.build().is_err());
+
+    let mut mutable = Command::builder();
+    mutable.executable("cargo".to_owned());
+    let copy = mutable.clone();
+    assert_eq!(copy.build().unwrap().executable, "cargo");
+}
+
+#[derive(Builder)]
+#[builder(pattern = "mutable")]
+pub struct Command {
+    executable: String,
+}
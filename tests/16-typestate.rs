@@ -0,0 +1,30 @@
+// With `#[builder(typestate)]` the builder tracks in its type which of the
+// required fields have been set. `build` only exists once all of them are, and
+// returns the struct directly instead of a `Result`. Optional and repeated
+// fields may be set or not, in any order.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command<T> {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    payload: T,
+}
+
+fn main() {
+    let builder = Command::builder().arg("build".to_owned());
+    let builder = builder.payload(42u8);
+    let command: Command<u8> = builder
+        .current_dir("..".to_owned())
+        .executable("cargo".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.payload, 42);
+}
@@ -0,0 +1,67 @@
+// `build` returns a generated `{Struct}BuilderError`, which can be matched on,
+// is `Send + Sync`, and names the struct when displayed. With
+// `#[builder(build_fn(error = "..."))]` it returns the given error instead,
+// converted with `From`.
+
+use std::fmt;
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    Incomplete(String),
+}
+
+impl From<JobBuilderError> for ConfigError {
+    fn from(error: JobBuilderError) -> Self {
+        ConfigError::Incomplete(error.to_string())
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Incomplete(message) => f.write_str(message),
+        }
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(error = "ConfigError"))]
+pub struct Job {
+    name: String,
+}
+
+fn assert_send_sync<T: Send + Sync + std::error::Error + 'static>(_: &T) {}
+
+fn main() {
+    let error = Command::builder()
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap_err();
+    assert_send_sync(&error);
+
+    match &error {
+        CommandBuilderError::UninitializedFields(fields) => assert_eq!(*fields, ["executable"]),
+        CommandBuilderError::ValidationError(_)
+        | CommandBuilderError::ConversionError { .. }
+        | CommandBuilderError::InvalidField { .. } => unreachable!(),
+    }
+    assert_eq!(
+        error.to_string(),
+        "`Command` is missing required field `executable`"
+    );
+
+    let error: ConfigError = Job::builder().build().unwrap_err();
+    assert_eq!(
+        error,
+        ConfigError::Incomplete("`Job` is missing required field `name`".to_owned())
+    );
+    assert_eq!(Job::builder().name("nightly".to_owned()).build().unwrap().name, "nightly");
+}
@@ -0,0 +1,39 @@
+// A failing `build` reports every required field that is missing, in
+// declaration order, instead of stopping at the first one.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    timeout: u64,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct Owned(String, u32);
+
+fn main() {
+    let error = Command::builder()
+        .env(vec![])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        CommandBuilderError::UninitializedFields(vec!["executable", "timeout"])
+    );
+    assert_eq!(
+        error.to_string(),
+        "`Command` is missing required fields `executable`, `timeout`"
+    );
+
+    let error = Owned::builder()._1(7).build().unwrap_err();
+    assert_eq!(error.to_string(), "`Owned` is missing required field `_0`");
+    assert_eq!(Owned::builder()._0("a".to_owned())._1(7).build().unwrap().1, 7);
+}
@@ -0,0 +1,73 @@
+// Fields with `#[builder(default)]` fall back to `Default::default()` when
+// unset, and fields with `#[builder(default = "...")]` to the given
+// expression, which may use the values of the fields declared before it. A
+// container-level `#[builder(default)]` takes every unset field from the
+// struct's own `Default` impl instead.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Endpoint {
+    scheme: String,
+    #[builder(default = "if scheme == \"https\" { 443 } else { 80 }")]
+    port: u16,
+    #[builder(default)]
+    path: String,
+    #[builder(default = "Some(30)")]
+    timeout: Option<u64>,
+}
+
+#[derive(Builder, Debug)]
+#[builder(default)]
+pub struct Limits {
+    connections: u32,
+    #[builder(default = "connections * 4")]
+    requests: u32,
+    label: Option<String>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            connections: 16,
+            requests: 0,
+            label: Some("default".to_owned()),
+        }
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(typestate)]
+pub struct Retry {
+    attempts: u32,
+    #[builder(default = "u64::from(attempts) * 100")]
+    backoff_ms: u64,
+}
+
+fn main() {
+    let endpoint = Endpoint::builder()
+        .scheme("https".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(endpoint.port, 443);
+    assert_eq!(endpoint.path, "");
+    assert_eq!(endpoint.timeout, Some(30));
+
+    let endpoint = Endpoint::builder()
+        .scheme("http".to_owned())
+        .path("/health".to_owned())
+        .timeout(5)
+        .build()
+        .unwrap();
+    assert_eq!(endpoint.port, 80);
+    assert_eq!(endpoint.path, "/health");
+    assert_eq!(endpoint.timeout, Some(5));
+
+    let limits = Limits::builder().connections(2).build().unwrap();
+    assert_eq!(limits.connections, 2);
+    assert_eq!(limits.requests, 8);
+    assert_eq!(limits.label.as_deref(), Some("default"));
+
+    let retry = Retry::builder().attempts(3).build();
+    assert_eq!(retry.backoff_ms, 300);
+}
@@ -0,0 +1,39 @@
+// Fields with `#[builder(skip)]` are kept out of the builder entirely: there is
+// no setter for them, and `build` initializes them with `Default::default()`,
+// or with the expression given in `#[builder(skip = "...")]`. Fields of type
+// `PhantomData` are skipped without asking.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Cache<K, V> {
+    capacity: usize,
+    #[builder(skip)]
+    entries: HashMap<K, V>,
+    #[builder(skip = "Cell::new(capacity / 2)")]
+    evict_at: Cell<usize>,
+    marker: PhantomData<fn() -> (K, V)>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Handle<T> {
+    id: u32,
+    #[builder(skip)]
+    hits: Cell<u64>,
+    _owner: PhantomData<T>,
+}
+
+fn main() {
+    let cache: Cache<String, u32> = Cache::builder().capacity(8).build().unwrap();
+    assert!(cache.entries.is_empty());
+    assert_eq!(cache.evict_at.get(), 4);
+    let _ = cache.marker;
+
+    let handle: Handle<String> = Handle::builder().id(1).build();
+    assert_eq!(handle.hits.get(), 0);
+}
@@ -0,0 +1,49 @@
+// With `#[builder(setter(into))]` on a field, its setter takes anything that
+// converts into the field's type, so a `&str` can be passed for a `String`.
+// `each(name = "...", into)` does the same for the setter of a single element,
+// and `#[builder(setter(into))]` on the struct applies to every field.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each(name = "arg", into))]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Server {
+    host: String,
+    port: u32,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Login {
+    #[builder(setter(into))]
+    user: String,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let server = Server::builder().host("localhost").port(8080u16).build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+
+    let login = Login::builder().user("root").build();
+    assert_eq!(login.user, "root");
+}
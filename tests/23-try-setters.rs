@@ -0,0 +1,59 @@
+// `#[builder(setter(try_into))]` adds a `try_` setter taking anything that
+// converts into the field's type with `TryInto`, and `#[builder(setter(parse))]`
+// one taking a `&str` that is parsed with `FromStr`. When the conversion fails,
+// the setter returns the builder's error, naming the field.
+
+use std::net::IpAddr;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(setter(parse))]
+    address: IpAddr,
+    #[builder(setter(try_into))]
+    port: u16,
+    #[builder(setter(parse))]
+    workers: Option<usize>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Limit {
+    #[builder(setter(try_into))]
+    max: u8,
+}
+
+fn main() {
+    let server = Server::builder()
+        .try_address("127.0.0.1")
+        .unwrap()
+        .try_port(8080u32)
+        .unwrap()
+        .try_workers("4")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(server.address, IpAddr::from([127, 0, 0, 1]));
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, Some(4));
+
+    let mut builder = Server::builder();
+    let error = builder.try_port(100_000u32).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "cannot set `port` of `Server`: out of range integral type conversion attempted",
+    );
+    let error = builder.try_workers("many").err().unwrap();
+    assert_eq!(
+        error,
+        ServerBuilderError::ConversionError {
+            field: "workers",
+            message: "invalid digit found in string".to_owned(),
+        },
+    );
+
+    let limit = Limit::builder().try_max(200i32).unwrap().build();
+    assert_eq!(limit.max, 200);
+    assert!(Limit::builder().try_max(-1i32).is_err());
+}
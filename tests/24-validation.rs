@@ -0,0 +1,93 @@
+// `#[builder(build_fn(validate = "path::to::fn"))]` has `build` pass the built
+// value to the function before returning it. The function returns
+// `Result<(), E>` with an error that can be displayed, such as a `String`, and
+// a failure is returned from `build` as a `ValidationError`. A field with the
+// name of the function does not hide it.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(validate = "Range::check"))]
+pub struct Range {
+    min: u32,
+    max: u32,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), String> {
+        if self.min <= self.max {
+            Ok(())
+        } else {
+            Err(format!("{} is above {}", self.min, self.max))
+        }
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(validate = "checks::tls"))]
+pub struct Listener {
+    tls: bool,
+    cert: Option<String>,
+}
+
+mod checks {
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub struct MissingCert;
+
+    impl fmt::Display for MissingCert {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("tls requires cert")
+        }
+    }
+
+    pub fn tls(listener: &super::Listener) -> Result<(), MissingCert> {
+        if listener.tls && listener.cert.is_none() {
+            Err(MissingCert)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(validate = "check"))]
+pub struct Quota {
+    check: u32,
+    limit: u32,
+}
+
+fn check(quota: &Quota) -> Result<(), &'static str> {
+    if quota.check <= quota.limit {
+        Ok(())
+    } else {
+        Err("check above limit")
+    }
+}
+
+fn main() {
+    let range = Range::builder().min(1).max(3).build().unwrap();
+    assert_eq!((range.min, range.max), (1, 3));
+
+    let error = Range::builder().min(5).max(3).build().unwrap_err();
+    assert_eq!(
+        error,
+        RangeBuilderError::ValidationError("5 is above 3".to_owned())
+    );
+    assert_eq!(error.to_string(), "invalid `Range`: 5 is above 3");
+
+    let error = Listener::builder().tls(true).build().unwrap_err();
+    assert_eq!(error.to_string(), "invalid `Listener`: tls requires cert");
+    let listener = Listener::builder()
+        .tls(true)
+        .cert("server.pem".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(listener.cert.as_deref(), Some("server.pem"));
+
+    let quota = Quota::builder().check(1).limit(2).build().unwrap();
+    assert_eq!(quota.check, 1);
+    let error = Quota::builder().check(3).limit(2).build().unwrap_err();
+    assert_eq!(error.to_string(), "invalid `Quota`: check above limit");
+}
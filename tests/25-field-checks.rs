@@ -0,0 +1,114 @@
+// Fields can be checked by `build` once they are all set: `validate = "..."`
+// calls a function with the value, `range(min = .., max = ..)` bounds a
+// number, `len(min = .., max = ..)` the length of a string or collection, and
+// `non_empty` requires one element. The first failing check is returned as an
+// `InvalidField` error naming the field and the rule. Unset optional fields are
+// not checked, and fields do not hide functions of the same name.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(non_empty, len(max = 64))]
+    host: String,
+    #[builder(range(min = 1, max = 65535))]
+    port: u32,
+    #[builder(each = "alias", len(max = 2))]
+    aliases: Vec<String>,
+    #[builder(validate = "checks::is_absolute")]
+    root: Option<String>,
+}
+
+#[derive(Builder, Debug)]
+pub struct Batch {
+    #[builder(validate = "even")]
+    size: u32,
+    even: bool,
+}
+
+fn even(size: &u32) -> Result<(), String> {
+    if size % 2 == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} is odd", size))
+    }
+}
+
+mod checks {
+    pub fn is_absolute(path: &String) -> Result<(), String> {
+        if path.starts_with('/') {
+            Ok(())
+        } else {
+            Err(format!("`{}` is relative", path))
+        }
+    }
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .port(8080)
+        .alias("local".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.root, None);
+
+    let error = Server::builder()
+        .host("localhost".to_owned())
+        .port(0)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error,
+        ServerBuilderError::InvalidField {
+            field: "port",
+            rule: "range(min = 1, max = 65535)",
+            message: "must be at least 1".to_owned(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "`port` of `Server` failed `range(min = 1, max = 65535)`: must be at least 1"
+    );
+
+    let error = Server::builder()
+        .host(String::new())
+        .port(80)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`host` of `Server` failed `non_empty`: must not be empty"
+    );
+
+    let error = Server::builder()
+        .host("localhost".to_owned())
+        .port(80)
+        .aliases(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()])
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`aliases` of `Server` failed `len(max = 2)`: length must be at most 2"
+    );
+
+    let error = Server::builder()
+        .host("localhost".to_owned())
+        .port(80)
+        .root("srv".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`root` of `Server` failed `validate = checks::is_absolute`: `srv` is relative"
+    );
+
+    let batch = Batch::builder().size(2).even(true).build().unwrap();
+    assert!(batch.even);
+    let error = Batch::builder().size(3).even(false).build().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`size` of `Batch` failed `validate = even`: 3 is odd"
+    );
+}
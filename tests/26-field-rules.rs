@@ -0,0 +1,59 @@
+// Rules on the struct relate its optional fields: `exactly_one_of(...)` and
+// `at_least_one_of(...)` count how many of them are set, and
+// `requires(a => b)` has `b` set whenever `a` is. They are checked by `build`,
+// which returns a `ValidationError` describing the broken rule.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(exactly_one_of(password, token, cert), requires(tls_key => tls_cert))]
+#[builder(at_least_one_of(host, socket))]
+pub struct Connection {
+    host: Option<String>,
+    socket: Option<String>,
+    password: Option<String>,
+    token: Option<String>,
+    cert: Option<String>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+}
+
+fn main() {
+    let connection = Connection::builder()
+        .host("db".to_owned())
+        .token("secret".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(connection.token.as_deref(), Some("secret"));
+
+    let error = Connection::builder()
+        .host("db".to_owned())
+        .password("hunter2".to_owned())
+        .token("secret".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid `Connection`: exactly one of `password`, `token`, `cert` must be set"
+    );
+
+    let error = Connection::builder()
+        .token("secret".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid `Connection`: at least one of `host`, `socket` must be set"
+    );
+
+    let error = Connection::builder()
+        .socket("/run/db.sock".to_owned())
+        .token("secret".to_owned())
+        .tls_key("key.pem".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error,
+        ConnectionBuilderError::ValidationError("`tls_key` requires `tls_cert` to be set".to_owned())
+    );
+}
@@ -0,0 +1,13 @@
+// Fields named by a rule have to exist, and be optional, so that a typo is
+// caught when the builder is derived rather than failing every `build`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(requires(tls_key => tls_crt))]
+pub struct Listener {
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,95 @@
+// `each` works on other collections than `Vec`: the element setter uses
+// `push_back` on a `VecDeque`, `insert` on sets, `insert(key, value)` on maps,
+// and `push` on a `String`, which is built from `char`s. Any other collection
+// that is `Extend + Default` is filled through `Extend` when marked with
+// `each(name = "...", collection)`, its elements being its first type argument,
+// or the type given with `collection = "..."` as for a collection of pairs or
+// one with no type arguments.
+// On an `Option` of a collection, the first element creates it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use derive_builder::Builder;
+
+#[derive(Clone, Default)]
+pub struct Log<T>(Vec<T>);
+
+impl<T> Extend<T> for Log<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Pairs<K, V>(Vec<(K, V)>);
+
+impl<K, V> Extend<(K, V)> for Pairs<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Names(Vec<String>);
+
+impl Extend<String> for Names {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "env")]
+    envs: HashMap<String, String>,
+    #[builder(each = "feature")]
+    features: BTreeSet<String>,
+    #[builder(each = "port")]
+    ports: HashSet<u16>,
+    #[builder(each = "header")]
+    headers: BTreeMap<String, u32>,
+    #[builder(each = "job")]
+    queue: VecDeque<u32>,
+    #[builder(each = "flag")]
+    flags: String,
+    #[builder(each(name = "entry", collection))]
+    log: Log<&'static str>,
+    #[builder(each(name = "label", collection = "(String, u32)"))]
+    labels: Pairs<String, u32>,
+    #[builder(each(name = "user", collection = String, into))]
+    users: Names,
+    #[builder(each = "arg")]
+    args: Option<Vec<String>>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .env("PATH".to_owned(), "/bin".to_owned())
+        .feature("b".to_owned())
+        .feature("a".to_owned())
+        .port(80)
+        .port(80)
+        .header("x-retries".to_owned(), 3)
+        .job(1)
+        .job(2)
+        .flag('v')
+        .flag('q')
+        .entry("started")
+        .label(("x".to_owned(), 1))
+        .user("root")
+        .build()
+        .unwrap();
+    assert_eq!(command.envs["PATH"], "/bin");
+    assert_eq!(command.features.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(command.ports.len(), 1);
+    assert_eq!(command.headers["x-retries"], 3);
+    assert_eq!(command.queue, [1, 2]);
+    assert_eq!(command.flags, "vq");
+    assert_eq!(command.log.0, ["started"]);
+    assert_eq!(command.labels.0, [("x".to_owned(), 1)]);
+    assert_eq!(command.users.0, ["root"]);
+    assert_eq!(command.args, None);
+
+    let command = Command::builder().arg("build".to_owned()).build().unwrap();
+    assert_eq!(command.args, Some(vec!["build".to_owned()]));
+}
@@ -0,0 +1,44 @@
+// Fields with `each` also get an `extend_` setter, adding every element of an
+// iterator, each converted with `Into`. With `each(replace = false)`, the
+// setter of the whole collection appends to it rather than replacing it.
+
+use std::collections::HashMap;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each(name = "env", replace = false))]
+    envs: HashMap<String, String>,
+    #[builder(each(name = "flag", replace = false))]
+    flags: String,
+}
+
+fn main() {
+    let defaults = vec![("HOME", "/root")];
+    let inherited = HashMap::from([("PATH".to_owned(), "/bin".to_owned())]);
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .extend_args(["--release", "--locked"])
+        .extend_envs(defaults.iter().map(|&(key, value)| (key.to_owned(), value.to_owned())))
+        .envs(inherited)
+        .env("TERM".to_owned(), "dumb".to_owned())
+        .flags("vq".to_owned())
+        .flags("s".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build", "--release", "--locked"]);
+    assert_eq!(command.envs.len(), 3);
+    assert_eq!(command.envs["HOME"], "/root");
+    assert_eq!(command.envs["PATH"], "/bin");
+    assert_eq!(command.flags, "vqs");
+
+    let command = Command::builder()
+        .extend_args(["first"])
+        .args(vec!["second".to_owned()])
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["second"]);
+}
@@ -0,0 +1,66 @@
+// A bare `#[builder(each)]` names the setter of one element after the field,
+// in the singular, by dropping its `s`. Where either `s` or `es` could be
+// dropped, as in `caches`, the field names it itself.
+
+use std::collections::HashMap;
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone)]
+pub struct Request {
+    #[builder(each)]
+    args: Vec<String>,
+    #[builder(each)]
+    entries: Vec<u32>,
+    #[builder(each)]
+    http_headers: HashMap<String, String>,
+    #[builder(each)]
+    children: Vec<Request>,
+    #[builder(each)]
+    addresses: Vec<String>,
+    #[builder(each(into))]
+    names: Vec<String>,
+    #[builder(each)]
+    values: Vec<u32>,
+    #[builder(each)]
+    files: Vec<String>,
+    #[builder(each)]
+    boxes: Vec<u32>,
+    #[builder(each = "size")]
+    sizes: Vec<u32>,
+    #[builder(each = "cache")]
+    caches: Vec<String>,
+    #[builder(each = "status")]
+    statuses: Vec<u16>,
+}
+
+fn main() {
+    let child = Request::builder().build().unwrap();
+    let request = Request::builder()
+        .arg("-v".to_owned())
+        .entry(1)
+        .http_header("accept".to_owned(), "*/*".to_owned())
+        .child(child)
+        .address("localhost".to_owned())
+        .name("req")
+        .value(7)
+        .file("Cargo.toml".to_owned())
+        .r#box(1)
+        .size(2)
+        .cache("disk".to_owned())
+        .status(200)
+        .build()
+        .unwrap();
+    assert_eq!(request.args, ["-v"]);
+    assert_eq!(request.entries, [1]);
+    assert_eq!(request.http_headers["accept"], "*/*");
+    assert_eq!(request.children.len(), 1);
+    assert_eq!(request.addresses, ["localhost"]);
+    assert_eq!(request.names, ["req"]);
+    assert_eq!(request.values, [7]);
+    assert_eq!(request.files, ["Cargo.toml"]);
+    assert_eq!(request.boxes, [1]);
+    assert_eq!(request.sizes, [2]);
+    assert_eq!(request.caches, ["disk"]);
+    assert_eq!(request.statuses, [200]);
+}
@@ -0,0 +1,30 @@
+// A bare `each` on a field whose name has no certain singular asks for an
+// explicit one, rather than guessing `siz`, `cach` or `statuse`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Packet {
+    #[builder(each)]
+    data: Vec<u8>,
+}
+
+#[derive(Builder)]
+pub struct Image {
+    #[builder(each)]
+    sizes: Vec<u32>,
+}
+
+#[derive(Builder)]
+pub struct Store {
+    #[builder(each)]
+    caches: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Response {
+    #[builder(each)]
+    statuses: Vec<u16>,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// When `each` is given the name of the field, the setter of one element takes
+// that name and the setter of the whole collection is left out. Names that are
+// keywords, from `each` or inferred, become raw identifiers.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Query {
+    #[builder(each = "args")]
+    args: Vec<String>,
+    #[builder(each = "type")]
+    types: Vec<String>,
+    #[builder(each)]
+    loops: Vec<String>,
+}
+
+fn main() {
+    let query = Query::builder()
+        .args("-v".to_owned())
+        .args("-q".to_owned())
+        .r#type("file".to_owned())
+        .r#loop("main".to_owned())
+        .extend_loops(["event"])
+        .build()
+        .unwrap();
+    assert_eq!(query.args, ["-v", "-q"]);
+    assert_eq!(query.types, ["file"]);
+    assert_eq!(query.loops, ["main", "event"]);
+}
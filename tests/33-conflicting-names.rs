@@ -0,0 +1,13 @@
+// Two methods of the builder cannot share a name, here the setter of one
+// `arg` and the setter of the field `arg`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    arg: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+// Attributes other than `builder` on a field are left alone, such as doc
+// comments and lint levels. A field whose `cfg` is disabled is removed before
+// the derive sees it, so it has no setter, and a `cfg_attr` that applies gives
+// its attributes to the derive as if they were written out.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Listener {
+    /// Address to bind to.
+    #[allow(dead_code)]
+    address: String,
+    #[cfg(any())]
+    tls_cert: String,
+    #[cfg(all())]
+    #[builder(each = "alias")]
+    aliases: Vec<String>,
+    #[cfg_attr(all(), builder(default = "8080"))]
+    port: u16,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Server {
+    #[doc = "Name in the logs."]
+    name: String,
+    #[cfg(all())]
+    workers: usize,
+}
+
+fn main() {
+    let listener = Listener::builder()
+        .address("localhost".to_owned())
+        .alias("local".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(listener.aliases, ["local"]);
+    assert_eq!(listener.port, 8080);
+
+    let error = Listener::builder().build().err().unwrap();
+    assert_eq!(error, ListenerBuilderError::UninitializedFields(vec!["address"]));
+
+    let server = Server::builder().name("api".to_owned()).workers(4).build();
+    assert_eq!((server.name.as_str(), server.workers), ("api", 4));
+}
@@ -0,0 +1,49 @@
+//! The builder, its error and all of their methods are documented. Setters take
+//! the doc comments of their field, or the ones given with
+//! `#[builder(doc = "...")]`, so that this compiles with `missing_docs` denied.
+
+#![deny(missing_docs)]
+
+use derive_builder::Builder;
+
+/// A command to run.
+#[derive(Builder)]
+pub struct Command {
+    /// The program to run.
+    pub executable: String,
+    /// Arguments of the program.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+    /// Directory to run in.
+    #[builder(doc = "Runs the command in `dir` rather than the current directory.")]
+    pub current_dir: Option<String>,
+    /// Seconds to wait for.
+    #[builder(default = "60", setter(try_into))]
+    pub timeout: u64,
+    /// Set once the command ran.
+    #[builder(skip)]
+    pub done: bool,
+}
+
+/// A command checked at compile time.
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    /// The name in logs.
+    pub name: String,
+    /// Seconds between runs.
+    #[builder(setter(try_into))]
+    pub every: u32,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("test".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.timeout, 60);
+
+    let job = Job::builder().name("nightly".to_owned()).every(86400).build();
+    assert_eq!(job.every, 86400);
+}
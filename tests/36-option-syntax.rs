@@ -0,0 +1,64 @@
+// A field may have several options in one `#[builder(...)]` and several such
+// attributes. Names can be given bare, `each = header`, defaults as expressions
+// rather than in a string, and `setter(...)` and `each(...)` take lists of
+// options, including the name of the method. A field with `each` starts with
+// its default, if it is given one.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use derive_builder::Builder;
+
+const LIMIT: u32 = 3;
+
+#[derive(Builder)]
+#[builder(pattern = owned, build_fn(error = "ClientError"))]
+pub struct Client {
+    #[builder(setter(into, name = "url"))]
+    endpoint: String,
+    #[builder(default = Duration::from_secs(5))]
+    timeout: Duration,
+    #[builder(default = "3")]
+    retries: u32,
+    #[builder(default = if LIMIT < 5 { 1 } else { 2 }, private)]
+    backoff: u32,
+    #[builder(default = HashMap::<String, u32>::from([("requests".into(), 10)]), private)]
+    limits: HashMap<String, u32>,
+    #[builder(each(name = "tag", into))]
+    tags: Vec<String>,
+    #[builder(each = "arg", default)]
+    #[builder(len(min = 1))]
+    args: Vec<String>,
+    #[builder(each = header, default = vec!["accept: */*".to_owned()])]
+    headers: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ClientError(String);
+
+impl From<ClientBuilderError> for ClientError {
+    fn from(error: ClientBuilderError) -> Self {
+        ClientError(error.to_string())
+    }
+}
+
+fn main() {
+    let client = Client::builder()
+        .url("https://example.com")
+        .tag("staging")
+        .arg("-v".to_owned())
+        .header("host: example.com".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(client.endpoint, "https://example.com");
+    assert_eq!(client.timeout, Duration::from_secs(5));
+    assert_eq!(client.retries, 3);
+    assert_eq!(client.backoff, 1);
+    assert_eq!(client.limits["requests"], 10);
+    assert_eq!(client.tags, ["staging"]);
+    assert_eq!(client.args, ["-v"]);
+    assert_eq!(client.headers, ["accept: */*", "host: example.com"]);
+
+    let error = Client::builder().url("https://example.com").build().err().unwrap();
+    assert!(error.0.contains("args"));
+}
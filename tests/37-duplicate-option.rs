@@ -0,0 +1,12 @@
+// An option given twice, even across attributes, is an error on the second.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    #[builder(default, each = "argument")]
+    args: Vec<String>,
+}
+
+fn main() {}
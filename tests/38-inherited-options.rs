@@ -0,0 +1,87 @@
+// Options on the struct apply to every field, which can override them: here
+// all setters take `Into` and are named `with_{field}`, except where a field
+// opts out with `setter(into = false)` or names its setter itself. Likewise a
+// `try_` setter given to every field is turned off with `try_into = false`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Default)]
+#[builder(pattern = owned, default, setter(into, prefix = "with"))]
+pub struct Server {
+    host: String,
+    #[builder(setter(into = false))]
+    port: u16,
+    #[builder(default = 4)]
+    workers: usize,
+    #[builder(name = "verbose")]
+    verbosity: u8,
+    #[builder(setter(prefix = "add"), each = "alias")]
+    aliases: Vec<String>,
+    #[builder(setter(parse))]
+    backlog: u32,
+}
+
+#[derive(Builder)]
+#[builder(typestate, setter(prefix = "with"))]
+pub struct Request {
+    url: String,
+    #[builder(setter(parse))]
+    retries: u8,
+}
+
+#[derive(Builder)]
+#[builder(setter(try_into))]
+pub struct Limits {
+    max: u64,
+    // Would clash with the field below if it kept its `try_` setter.
+    #[builder(setter(try_into = false))]
+    name: String,
+    try_name: bool,
+}
+
+fn main() {
+    let server = Server::builder()
+        .with_host("localhost")
+        .with_port(8080)
+        .verbose(2)
+        .alias("www")
+        .add_aliases(vec!["api".to_owned()])
+        .try_with_backlog("128")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, 4);
+    assert_eq!(server.verbosity, 2);
+    assert_eq!(server.aliases, ["api"]);
+    assert_eq!(server.backlog, 128);
+
+    let server = Server::builder().build().unwrap();
+    assert_eq!(server.host, "");
+    assert_eq!(server.workers, 4);
+
+    let request = Request::builder()
+        .with_url("https://example.com".to_owned())
+        .try_with_retries("3")
+        .unwrap()
+        .build();
+    assert_eq!(request.url, "https://example.com");
+    assert_eq!(request.retries, 3);
+
+    let limits = Limits::builder()
+        .try_max(-1i64)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(limits.contains("max"));
+    let limits = Limits::builder()
+        .try_max(5u8)
+        .unwrap()
+        .name("default".to_owned())
+        .try_name(true)
+        .build()
+        .unwrap();
+    assert_eq!(limits.max, 5);
+    assert!(limits.try_name);
+}
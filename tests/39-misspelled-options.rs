@@ -0,0 +1,30 @@
+// An unknown option suggests the closest known one, also one that belongs in
+// a list such as `setter(...)`, and otherwise lists the valid options.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(patern = owned)]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(setter(inot))]
+    host: String,
+}
+
+#[derive(Builder)]
+pub struct Client {
+    #[builder(into)]
+    endpoint: String,
+}
+
+#[derive(Builder)]
+pub struct Request {
+    #[builder(frobnicate)]
+    url: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// A default that is not an expression is an error on the attribute, rather
+// than in the code generated from it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(default = "1 +")]
+    retries: u32,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+// Rules name the fields of one builder, so on an enum they go on its variants.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(at_least_one_of(host, socket))]
+pub enum Target {
+    #[builder(at_least_one_of(host, socket))]
+    Remote {
+        host: Option<String>,
+        socket: Option<String>,
+    },
+    Local(u32),
+}
+
+fn main() {}
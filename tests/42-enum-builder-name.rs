@@ -0,0 +1,13 @@
+// An enum gets one builder per variant, so naming its builder is an error even
+// when the name is the one it would have had.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(name = "MessageBuilder")]
+pub enum Message {
+    Text(String),
+    Quit(u8),
+}
+
+fn main() {}
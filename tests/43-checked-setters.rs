@@ -0,0 +1,91 @@
+// `#[builder(setter(checked))]` runs the checks of a field in its setters
+// instead of in `build`: the setter returns the `InvalidField` error of the
+// first check the value does not pass. Given on the struct, it applies to every
+// checked field but those with `each`, which are still checked by `build`. It
+// lets the fields of a typestate builder, whose `build` cannot fail, be checked.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(range(min = 1), setter(checked))]
+    port: u16,
+    #[builder(validate = "check", setter(checked, parse))]
+    check: Option<u32>,
+    #[builder(len(max = 8))]
+    host: String,
+}
+
+fn check(value: &u32) -> Result<(), String> {
+    if value % 2 == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} is odd", value))
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(typestate, setter(checked))]
+pub struct Job {
+    #[builder(non_empty, setter(try_into))]
+    name: String,
+    #[builder(range(max = 10))]
+    priority: Option<u8>,
+}
+
+fn main() {
+    let error = Server::builder().port(0).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "`port` of `Server` failed `range(min = 1)`: must be at least 1"
+    );
+
+    let error = Server::builder().try_check("3").err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "`check` of `Server` failed `validate = check`: 3 is odd"
+    );
+
+    let server = Server::builder()
+        .port(80)
+        .unwrap()
+        .check(2)
+        .unwrap()
+        .host("local".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 80);
+    assert_eq!(server.check, Some(2));
+
+    let error = Server::builder()
+        .port(80)
+        .unwrap()
+        .host("example.com".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`host` of `Server` failed `len(max = 8)`: length must be at most 8"
+    );
+
+    let error = Job::builder().name(String::new()).err().unwrap();
+    assert_eq!(
+        error,
+        JobBuilderError::InvalidField {
+            field: "name",
+            rule: "non_empty",
+            message: "must not be empty".to_owned(),
+        }
+    );
+    assert!(Job::builder().try_name("").is_err());
+    assert!(Job::builder().priority(11).is_err());
+
+    let job: Job = Job::builder()
+        .try_name("backup")
+        .unwrap()
+        .priority(3)
+        .unwrap()
+        .build();
+    assert_eq!(job.name, "backup");
+    assert_eq!(job.priority, Some(3));
+}
@@ -0,0 +1,13 @@
+// A rule that cannot be parsed is an error on the rule rather than on the
+// derive, even when the list ends before the rule does.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(requires(token))]
+pub struct Request {
+    token: Option<String>,
+    user: Option<String>,
+}
+
+fn main() {}
@@ -10,4 +10,39 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-builder-name.rs");
+    t.pass("tests/11-generics.rs");
+    t.pass("tests/12-visibility.rs");
+    t.pass("tests/13-tuple-structs.rs");
+    t.pass("tests/14-enum-variants.rs");
+    t.pass("tests/15-builder-patterns.rs");
+    t.pass("tests/16-typestate.rs");
+    t.compile_fail("tests/17-typestate-missing-field.rs");
+    t.pass("tests/18-error-type.rs");
+    t.pass("tests/19-all-missing-fields.rs");
+    t.pass("tests/20-defaults.rs");
+    t.pass("tests/21-skipped-fields.rs");
+    t.pass("tests/22-setter-into.rs");
+    t.pass("tests/23-try-setters.rs");
+    t.pass("tests/24-validation.rs");
+    t.pass("tests/25-field-checks.rs");
+    t.pass("tests/26-field-rules.rs");
+    t.compile_fail("tests/27-unknown-rule-field.rs");
+    t.pass("tests/28-each-collections.rs");
+    t.pass("tests/29-extend-setters.rs");
+    t.pass("tests/30-singular-each.rs");
+    t.compile_fail("tests/31-each-without-singular.rs");
+    t.pass("tests/32-method-names.rs");
+    t.compile_fail("tests/33-conflicting-names.rs");
+    t.pass("tests/34-foreign-attributes.rs");
+    t.pass("tests/35-docs.rs");
+    t.pass("tests/36-option-syntax.rs");
+    t.compile_fail("tests/37-duplicate-option.rs");
+    t.pass("tests/38-inherited-options.rs");
+    t.compile_fail("tests/39-misspelled-options.rs");
+    t.compile_fail("tests/40-invalid-default.rs");
+    t.compile_fail("tests/41-enum-rules.rs");
+    t.compile_fail("tests/42-enum-builder-name.rs");
+    t.pass("tests/43-checked-setters.rs");
+    t.compile_fail("tests/44-invalid-rule.rs");
 }